@@ -0,0 +1,126 @@
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use stick_path::generator::{self, Rng};
+use stick_path::map::{Direction, Map};
+
+const HUGE_MAP: &str = include_str!("../maps/huge_map.txt");
+
+/// Generated map sizes (columns, lines) held in memory, for parsing, validation and solving.
+const SIZES: [(usize, usize); 2] = [(10, 1_000), (100, 10_000)];
+
+/// Sizes too large to be held in memory (a 10,000x1,000,000 map would take about 80 GB of characters),
+/// only the walk of a single entry is timed on them. The last one is only run with
+/// `STICK_PATH_BENCH_FULL=1`: a single walk takes about 2.5 minutes.
+const STREAMED_SIZES: [(usize, usize); 2] = [(1_000, 100_000), (10_000, 1_000_000)];
+const DEFAULT_STREAMED_SIZES: usize = 1;
+
+/// Lines of each chunk of a streamed ladder, and number of distinct chunks it is made of.
+const CHUNK_LINES: usize = 100;
+const CHUNK_POOL: usize = 8;
+
+fn streamed_sizes() -> &'static [(usize, usize)] {
+    if std::env::var("STICK_PATH_BENCH_FULL").is_ok_and(|full| full == "1") {
+        &STREAMED_SIZES
+    } else {
+        &STREAMED_SIZES[..DEFAULT_STREAMED_SIZES]
+    }
+}
+
+/// Ladder generated lazily: its lines are chunks drawn from a small pool of generated maps,
+/// in an order drawn from the same seed, so that only the pool is held in memory.
+struct StreamedLadder {
+    pool: Vec<Map>,
+    order: Vec<usize>,
+}
+
+impl StreamedLadder {
+    fn new(columns: usize, lines: usize, rng: &mut Rng) -> StreamedLadder {
+        let pool = (0..CHUNK_POOL)
+            .map(|_| generator::generate(columns, CHUNK_LINES, 0.5, false, rng))
+            .collect();
+        let order = (0..lines.div_ceil(CHUNK_LINES))
+            .map(|_| (rng.next_u64() % CHUNK_POOL as u64) as usize)
+            .collect();
+
+        StreamedLadder { pool, order }
+    }
+
+    /// Walks the entry of the column down every chunk, returning its exit column.
+    fn exit_column(&self, mut column: usize) -> usize {
+        for chunk in &self.order {
+            let chunk = &self.pool[*chunk];
+            let (steps, _) = chunk
+                .walk(&chunk.entries[column], Direction::Down)
+                .expect("Could not walk chunk");
+
+            column = steps.last().map_or(column, |(_, x)| *x);
+        }
+        column
+    }
+}
+
+fn huge_map(c: &mut Criterion) {
+    let map = HUGE_MAP.parse::<Map>().expect("Could not parse map");
+    let mut group = c.benchmark_group("huge_map");
+
+    group.bench_function("parse", |b| b.iter(|| black_box(HUGE_MAP).parse::<Map>()));
+    group.bench_function("validate", |b| b.iter(|| black_box(&map).validate()));
+    group.bench_function("exit_for_entry", |b| {
+        b.iter(|| black_box(&map).exit_for_entry("P"))
+    });
+    group.bench_function("solve", |b| b.iter(|| black_box(&map).solve()));
+    group.finish();
+}
+
+fn generated_maps(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generated");
+
+    group.sample_size(10);
+    for (columns, lines) in &SIZES {
+        let map = generator::generate(*columns, *lines, 0.5, false, &mut Rng::new(42));
+        let text = map.to_string();
+        let size = format!("{}x{}", columns, lines);
+
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::new("parse", &size), &text, |b, text| {
+            b.iter(|| text.parse::<Map>())
+        });
+        group.throughput(Throughput::Elements(*lines as u64));
+        group.bench_with_input(BenchmarkId::new("validate", &size), &map, |b, map| {
+            b.iter(|| map.validate())
+        });
+        group.bench_with_input(BenchmarkId::new("exit_for_entry", &size), &map, |b, map| {
+            b.iter(|| map.exit_for_entry(&map.entries[columns / 2]))
+        });
+        group.throughput(Throughput::Elements((*lines * *columns) as u64));
+        group.bench_with_input(BenchmarkId::new("solve", &size), &map, |b, map| {
+            b.iter(|| map.solve())
+        });
+    }
+    group.finish();
+}
+
+fn streamed_maps(c: &mut Criterion) {
+    let mut group = c.benchmark_group("streamed");
+
+    // A single walk of the smallest streamed size takes about a second
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(15));
+    for (columns, lines) in streamed_sizes() {
+        let ladder = StreamedLadder::new(*columns, *lines, &mut Rng::new(42));
+        let size = format!("{}x{}", columns, lines);
+
+        group.throughput(Throughput::Elements(*lines as u64));
+        group.bench_with_input(
+            BenchmarkId::new("exit_column", &size),
+            &ladder,
+            |b, ladder| b.iter(|| ladder.exit_column(black_box(columns / 2))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, huge_map, generated_maps, streamed_maps);
+criterion_main!(benches);
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use stick_path::map::Map;
+
+fuzz_target!(|input: (usize, usize, Vec<String>)| {
+    let (width, height, content) = input;
+
+    let _ = Map::from_size_and_content(width, height, content);
+});
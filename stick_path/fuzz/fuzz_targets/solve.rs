@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use stick_path::map::Map;
+
+// The solver must not panic on maps that do not pass the validation either
+fuzz_target!(|data: &[u8]| {
+    if let Ok(mut map) = String::from_utf8_lossy(data).parse::<Map>() {
+        for circular in [false, true] {
+            map.circular = circular;
+            for entry in map.entries.clone() {
+                let _ = map.trace_for_entry(&entry);
+                let _ = map.exit_for_entry(&entry);
+            }
+        }
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use stick_path::map::Map;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(mut map) = String::from_utf8_lossy(data).parse::<Map>() {
+        for circular in [false, true] {
+            map.circular = circular;
+            let _ = map.validate();
+        }
+    }
+});
@@ -0,0 +1,146 @@
+use crate::map::Map;
+
+/// Actions the viewer can request from an animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    TogglePause,
+    Step,
+    NextEntry,
+    PreviousEntry,
+    Quit,
+}
+
+/// Walk of one entry down a map, one line at a time.
+/// Does not know anything about the terminal so it can be driven by tests.
+#[derive(Debug)]
+pub struct Animation<'a> {
+    map: &'a Map,
+    pub entry: usize,
+    pub line: usize,
+    pub path: Vec<usize>,
+    pub paused: bool,
+    /// Column of the obstacle stopping the walker, on the current line.
+    pub trapped: Option<usize>,
+}
+
+impl<'a> Animation<'a> {
+    /// Creates an animation starting at the top of the map for the entry at the provided index.
+    pub fn new(map: &'a Map, entry: usize) -> Result<Animation<'a>, String> {
+        map.check_no_portals("Viewer")?;
+        if entry < map.entries.len() {
+            Ok(Animation {
+                map,
+                entry,
+                line: 0,
+                path: vec![entry],
+                paused: false,
+                trapped: None,
+            })
+        } else {
+            Err("Entry not found in map".to_owned())
+        }
+    }
+
+    /// Tells you if the walker reached the bottom of the map or got trapped.
+    pub fn is_finished(&self) -> bool {
+        self.line >= self.map.height || self.trapped.is_some()
+    }
+
+    /// Returns the column the walker is currently on.
+    pub fn column(&self) -> usize {
+        self.path[self.path.len() - 1]
+    }
+
+    /// Returns the exit reached by the walker, once the animation is finished.
+    pub fn exit(&self) -> Option<String> {
+        if self.is_finished() && self.trapped.is_none() {
+            self.map.exit_for_column(self.column()).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Moves the walker down one line, using the same stepping logic as `Map.exit_for_entry`.
+    pub fn step(&mut self) -> Result<(), String> {
+        if !self.is_finished() {
+            let column = self.map.next_column_for_coords(self.column(), self.line)?;
+
+            if self.map.is_blocked(column, self.line)? {
+                self.trapped = Some(column);
+            } else {
+                self.path.push(column);
+                self.line += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Advances the animation by one frame. Does nothing while paused.
+    pub fn tick(&mut self) -> Result<(), String> {
+        if self.paused {
+            Ok(())
+        } else {
+            self.step()
+        }
+    }
+
+    /// Applies the provided key to the animation. Returns `false` when the viewer should quit.
+    pub fn handle_key(&mut self, key: Key) -> Result<bool, String> {
+        let entries = self.map.entries.len();
+
+        match key {
+            Key::TogglePause => self.paused = !self.paused,
+            Key::Step => {
+                self.paused = true;
+                self.step()?;
+            }
+            Key::NextEntry => self.restart((self.entry + 1) % entries),
+            Key::PreviousEntry => self.restart((self.entry + entries - 1) % entries),
+            Key::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Restarts the walk from the top of the map for another entry, keeping the pause state.
+    fn restart(&mut self, entry: usize) {
+        self.entry = entry;
+        self.line = 0;
+        self.path = vec![entry];
+        self.trapped = None;
+    }
+
+    /// Renders the map with the walked path ('*') and the walker ('o').
+    pub fn frame(&self) -> String {
+        let mut lines = vec![self.map.entries.join(" ")];
+
+        for (y, line) in self.map.content.iter().enumerate() {
+            let line = Map::render_line(line);
+
+            lines.push(match self.path.get(y) {
+                Some(column) => {
+                    Map::mark_column(&line, *column, if y == self.line { 'o' } else { '*' })
+                }
+                None => line,
+            });
+        }
+        lines.push(self.map.exits.join(" "));
+        lines.push(String::new());
+        lines.push(match (self.exit(), self.trapped) {
+            (Some(exit), _) => format!("{} reaches {}", self.map.entries[self.entry], exit),
+            (None, Some(column)) => format!(
+                "{} is trapped on line {} column {}",
+                self.map.entries[self.entry],
+                self.line + 1,
+                column + 1
+            ),
+            (None, None) => format!(
+                "{} - line {}/{}{}",
+                self.map.entries[self.entry],
+                self.line + 1,
+                self.map.height,
+                if self.paused { " (paused)" } else { "" }
+            ),
+        });
+        lines.join("\n")
+    }
+}
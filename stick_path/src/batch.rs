@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::json;
+use crate::map::Map;
+
+/// Outcome of solving one map file of a batch.
+#[derive(Debug)]
+pub struct BatchResult {
+    pub path: PathBuf,
+    pub duration: Duration,
+    /// Exit reached by each entry, or the reason the map could not be solved.
+    pub outcome: Result<Vec<(String, String)>, String>,
+}
+
+impl BatchResult {
+    /// Formats the result as a single JSON line.
+    pub fn to_json(&self) -> String {
+        let path = json::string(&self.path.to_string_lossy());
+        let duration_ms = self.duration.as_secs_f64() * 1000.0;
+
+        match &self.outcome {
+            Ok(exits) => format!(
+                "{{\"path\":{},\"status\":\"ok\",\"duration_ms\":{:.3},\"exits\":[{}]}}",
+                path,
+                duration_ms,
+                exits
+                    .iter()
+                    .map(|(entry, exit)| format!(
+                        "{{\"entry\":{},\"exit\":{}}}",
+                        json::string(entry),
+                        json::string(exit)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            Err(err) => format!(
+                "{{\"path\":{},\"status\":\"error\",\"duration_ms\":{:.3},\"error\":{}}}",
+                path,
+                duration_ms,
+                json::string(err)
+            ),
+        }
+    }
+}
+
+/// Lists the maps to solve: the `.txt` files of a directory, or the paths listed in a manifest file
+/// (one per line, relative to the manifest, blank lines and lines starting with '#' being ignored).
+pub fn map_paths(source: &Path) -> Result<Vec<PathBuf>, String> {
+    if source.is_dir() {
+        let mut paths = fs::read_dir(source)
+            .map_err(|e| format!("Could not read directory {}: {}", source.display(), e))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Could not read directory {}: {}", source.display(), e))?;
+
+        paths.retain(|path| path.extension().is_some_and(|extension| extension == "txt"));
+        paths.sort();
+        Ok(paths)
+    } else {
+        let manifest = fs::read_to_string(source)
+            .map_err(|e| format!("Could not read manifest {}: {}", source.display(), e))?;
+        let base = source.parent().unwrap_or(Path::new(""));
+
+        Ok(manifest
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| base.join(line))
+            .collect())
+    }
+}
+
+/// Reads and validates a single map file.
+pub fn read_map(path: &Path, circular: bool) -> Result<Map, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Could not read map: {}", e))?;
+    let mut map = text.parse::<Map>()?;
+
+    map.circular = circular;
+    map.validate()?;
+    Ok(map)
+}
+
+/// Reads, validates and solves a single map file.
+pub fn solve_file(path: &Path, circular: bool) -> Result<Vec<(String, String)>, String> {
+    read_map(path, circular)?.solve()
+}
+
+/// Solves the maps on `workers` threads, calling `on_result` for each of them in the order of `paths`.
+/// A map that fails (or even panics) only gives an error result, the other maps are still solved.
+pub fn run(
+    paths: Vec<PathBuf>,
+    workers: usize,
+    circular: bool,
+    mut on_result: impl FnMut(BatchResult),
+) {
+    let paths = Arc::new(paths);
+    let next = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+    let handles = (0..workers.max(1))
+        .map(|_| {
+            let paths = Arc::clone(&paths);
+            let next = Arc::clone(&next);
+            let sender = sender.clone();
+
+            thread::spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let path = match paths.get(index) {
+                    Some(path) => path,
+                    None => break,
+                };
+                let start = Instant::now();
+                let outcome = panic::catch_unwind(|| solve_file(path, circular))
+                    .unwrap_or_else(|_| Err("Solver panicked".to_owned()));
+                let result = BatchResult {
+                    path: path.to_owned(),
+                    duration: start.elapsed(),
+                    outcome,
+                };
+
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    drop(sender);
+
+    // Results come in any order, they are kept until all the previous ones are out
+    let mut pending = BTreeMap::new();
+    let mut next_out = 0;
+
+    for (index, result) in receiver {
+        pending.insert(index, result);
+        while let Some(result) = pending.remove(&next_out) {
+            on_result(result);
+            next_out += 1;
+        }
+    }
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
@@ -0,0 +1,55 @@
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::time::Duration;
+
+use stick_path::error::CliError;
+use stick_path::server::{Config, Server};
+
+/// Parses the value of the `--name=VALUE` argument, if provided.
+fn arg<T: std::str::FromStr>(args: &[String], name: &str) -> Result<Option<T>, CliError> {
+    args.iter()
+        .find_map(|arg| arg.strip_prefix(&format!("--{}=", name)))
+        .map(|value| {
+            value
+                .parse::<T>()
+                .map_err(|_| CliError::usage(format!("Invalid value for --{}", name)))
+        })
+        .transpose()
+}
+
+/// Parses the value of a `--name=MS` argument, a duration of at least a millisecond.
+fn duration_arg(args: &[String], name: &str) -> Result<Option<Duration>, CliError> {
+    Ok(arg::<NonZeroU64>(args, name)?.map(|ms| Duration::from_millis(ms.get())))
+}
+
+/// Starts the server with the limits of the arguments (or their defaults), printing the address listened to.
+fn run(args: &[String]) -> Result<(), CliError> {
+    let defaults = Config::default();
+    let addr = arg::<String>(args, "addr")?.unwrap_or("127.0.0.1:8080".to_owned());
+    let config = Config {
+        max_body: arg(args, "max-body")?.unwrap_or(defaults.max_body),
+        timeout: duration_arg(args, "timeout-ms")?.unwrap_or(defaults.timeout),
+        deadline: duration_arg(args, "deadline-ms")?.unwrap_or(defaults.deadline),
+        workers: arg::<NonZeroUsize>(args, "workers")?.map_or(defaults.workers, NonZeroUsize::get),
+        max_generated_cells: arg(args, "max-cells")?.unwrap_or(defaults.max_generated_cells),
+    };
+
+    config.check().map_err(CliError::usage)?;
+
+    let server = Server::bind(&addr, config).map_err(CliError::io)?;
+
+    println!(
+        "Listening on {}",
+        server.local_addr().map_err(CliError::io)?
+    );
+    server.run();
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let Err(err) = run(&args) {
+        eprintln!("{}", err);
+        std::process::exit(err.kind.exit_code());
+    }
+}
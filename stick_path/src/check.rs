@@ -0,0 +1,102 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::batch;
+use crate::map::Map;
+
+/// Difference between the expected exit of an entry and the exit found by the solver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub entry: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.expected, &self.actual) {
+            (Some(expected), Some(actual)) => {
+                write!(f, "{} expected {}, got {}", self.entry, expected, actual)
+            }
+            (Some(expected), None) => {
+                write!(f, "{} expected {}, got nothing", self.entry, expected)
+            }
+            (None, Some(actual)) => write!(f, "{} not expected, got {}", self.entry, actual),
+            (None, None) => write!(f, "{} not expected", self.entry),
+        }
+    }
+}
+
+/// Returns the path of the expected answers of a map (`foo.txt` => `foo.expected`).
+pub fn expected_path(map_path: &Path) -> PathBuf {
+    map_path.with_extension("expected")
+}
+
+/// Parses expected answers in the `{entry}{exit}` format printed when solving a map.
+/// Labels are not separated so each line is split after the longest entry of the map it starts with.
+pub fn parse_expected(text: &str, map: &Map) -> Result<Vec<(String, String)>, String> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            map.entries
+                .iter()
+                .filter(|entry| line.starts_with(entry.as_str()))
+                .max_by_key(|entry| entry.len())
+                .map(|entry| (entry.to_owned(), line[entry.len()..].to_owned()))
+                .ok_or(format!(
+                    "Expected line \"{}\" does not start with an entry",
+                    line
+                ))
+        })
+        .collect()
+}
+
+/// Compares the expected exits with the exits found, in the order of the expected ones.
+pub fn compare(expected: &[(String, String)], actual: &[(String, String)]) -> Vec<Mismatch> {
+    let find = |pairs: &[(String, String)], entry: &str| {
+        pairs
+            .iter()
+            .find(|(other, _)| other == entry)
+            .map(|(_, exit)| exit.to_owned())
+    };
+    let mut mismatches = vec![];
+
+    for (entry, exit) in expected {
+        let found = find(actual, entry);
+
+        if found.as_ref() != Some(exit) {
+            mismatches.push(Mismatch {
+                entry: entry.to_owned(),
+                expected: Some(exit.to_owned()),
+                actual: found,
+            });
+        }
+    }
+    for (entry, exit) in actual {
+        if find(expected, entry).is_none() {
+            mismatches.push(Mismatch {
+                entry: entry.to_owned(),
+                expected: None,
+                actual: Some(exit.to_owned()),
+            });
+        }
+    }
+    mismatches
+}
+
+/// Solves a map file and compares its exits with the ones of its `.expected` file.
+pub fn check_file(path: &Path, circular: bool) -> Result<Vec<Mismatch>, String> {
+    let map = batch::read_map(path, circular)?;
+    let expected_path = expected_path(path);
+    let expected = fs::read_to_string(&expected_path).map_err(|e| {
+        format!(
+            "Could not read expected file {}: {}",
+            expected_path.display(),
+            e
+        )
+    })?;
+
+    Ok(compare(&parse_expected(&expected, &map)?, &map.solve()?))
+}
@@ -0,0 +1,100 @@
+use crate::map::{Map, OBSTACLE};
+
+/// Rung placed on a level of a compacted map: the column on its left and its weight if any.
+type PlacedRung = (usize, Option<i64>);
+
+impl Map {
+    /// Returns the same ladder with every rung moved as high as it can go without passing a rung
+    /// sharing one of its columns (the only ones it does not commute with), so every entry keeps its exit.
+    /// Rung weights are kept, maps with obstacles can not be compacted as obstacles belong to their line,
+    /// neither can maps with layers as rungs would move from one layer to another.
+    pub fn compact(&self) -> Result<Map, String> {
+        if !self.layers.is_empty() {
+            return Err("Maps with layers can not be compacted".to_owned());
+        }
+
+        let columns = self.entries.len();
+        let mut levels: Vec<Vec<PlacedRung>> = vec![];
+        // Level below the last rung placed on each slot
+        let mut free = vec![0; columns];
+
+        for y in 0..self.height {
+            if self.content[y].contains(&OBSTACLE) {
+                return Err("Maps with obstacles can not be compacted".to_owned());
+            }
+
+            let rungs = self.rungs_for_line(y)?;
+
+            if rungs.len() != columns {
+                return Err(format!(
+                    "Line {} does not have as many columns as entries",
+                    y + 1
+                ));
+            }
+            for (x, _) in rungs.iter().enumerate().filter(|(_, rung)| **rung) {
+                let left = (x + columns - 1) % columns;
+                let right = (x + 1) % columns;
+                let level = free[x].max(free[left]).max(free[right]);
+                let weight = Map::weight_in_segment(&self.rung_segment(x, y)?);
+
+                if level == levels.len() {
+                    levels.push(vec![]);
+                }
+                levels[level].push((x, weight));
+                free[x] = level + 1;
+            }
+        }
+
+        let content = levels
+            .iter()
+            .map(|rungs| Map::line_for_placed_rungs(rungs, columns, self.circular))
+            .collect::<Vec<_>>();
+        let width = content
+            .iter()
+            .map(|line| Map::render_line(line).chars().count())
+            .chain([self.width])
+            .max()
+            .unwrap_or(0);
+
+        Ok(Map {
+            width,
+            height: content.len(),
+            entries: self.entries.clone(),
+            exits: self.exits.clone(),
+            content,
+            circular: self.circular,
+            ..Map::default()
+        })
+    }
+
+    /// Builds a content line from its rungs, writing their weights back inside them.
+    fn line_for_placed_rungs(rungs: &[PlacedRung], columns: usize, circular: bool) -> Vec<char> {
+        let segment = |weight: &Option<i64>| match weight {
+            Some(weight) if *weight < 0 => format!("-+{}-", -weight),
+            Some(weight) => format!("-{}-", weight),
+            None => "--".to_owned(),
+        };
+        let wrap = rungs
+            .iter()
+            .find(|(x, _)| circular && columns > 1 && *x + 1 == columns);
+        let mut line = String::new();
+
+        if wrap.is_some() {
+            line.push('-');
+        }
+        for x in 0..columns {
+            line.push('|');
+            if x + 1 < columns {
+                if let Some((_, weight)) = rungs.iter().find(|(rung, _)| *rung == x) {
+                    line.push_str(&segment(weight));
+                }
+            }
+        }
+        match wrap {
+            Some((_, None)) => line.push('-'),
+            Some((_, weight)) => line.push_str(&segment(weight)),
+            None => (),
+        }
+        line.chars().collect()
+    }
+}
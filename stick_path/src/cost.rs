@@ -0,0 +1,77 @@
+use std::fmt;
+
+use crate::map::{Map, Outcome};
+
+/// Where the walk of an entry ends and what it cost on the way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntryCost {
+    pub entry: String,
+    pub outcome: Outcome,
+    pub cost: i64,
+}
+
+/// Cheapest and most expensive entries among the ones reaching an exit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostSummary {
+    pub cheapest: Vec<String>,
+    pub cheapest_cost: i64,
+    pub most_expensive: Vec<String>,
+    pub most_expensive_cost: i64,
+}
+
+impl fmt::Display for CostSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Cheapest: {} ({})",
+            self.cheapest.join(" "),
+            self.cheapest_cost
+        )?;
+        writeln!(
+            f,
+            "Most expensive: {} ({})",
+            self.most_expensive.join(" "),
+            self.most_expensive_cost
+        )
+    }
+}
+
+/// Walks every entry, in the order of the entries, summing the weights of the rungs taken.
+pub fn costs(map: &Map) -> Result<Vec<EntryCost>, String> {
+    map.entries
+        .iter()
+        .map(|entry| {
+            Ok(EntryCost {
+                entry: entry.to_owned(),
+                outcome: map.outcome_for_entry(entry)?,
+                cost: map.cost_for_entry(entry)?,
+            })
+        })
+        .collect()
+}
+
+/// Finds the cheapest and most expensive entries, ties being listed together.
+/// Trapped entries are left out, returns `None` if no entry reaches an exit.
+pub fn summary(costs: &[EntryCost]) -> Option<CostSummary> {
+    let arrived = costs
+        .iter()
+        .filter(|cost| matches!(cost.outcome, Outcome::Exit(_)))
+        .collect::<Vec<_>>();
+    let cheapest_cost = arrived.iter().map(|cost| cost.cost).min()?;
+    let most_expensive_cost = arrived.iter().map(|cost| cost.cost).max()?;
+    let entries_costing = |total: i64| {
+        arrived
+            .iter()
+            .filter(|cost| cost.cost == total)
+            .map(|cost| cost.entry.to_owned())
+            .collect()
+    };
+
+    Some(CostSummary {
+        cheapest: entries_costing(cheapest_cost),
+        cheapest_cost,
+        most_expensive: entries_costing(most_expensive_cost),
+        most_expensive_cost,
+    })
+}
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::map::Map;
+
+/// Largest number of columns handled, as every ordering of the columns can be a state.
+pub const MAX_COLUMNS: usize = 9;
+
+/// Returns every line of rungs that can be written with the provided number of columns,
+/// no column being linked on both sides (cf. rungs_for_line).
+pub fn rows(columns: usize, circular: bool) -> Vec<Vec<bool>> {
+    let slots = if circular && columns > 1 {
+        columns
+    } else {
+        columns.saturating_sub(1)
+    };
+
+    (0..1usize << slots)
+        .map(|bits| {
+            (0..columns)
+                .map(|x| x < slots && bits & (1 << x) != 0)
+                .collect::<Vec<_>>()
+        })
+        .filter(|rungs| (0..slots).all(|x| !(rungs[x] && rungs[(x + 1) % columns])))
+        .collect()
+}
+
+/// Returns the column reached at the bottom of a line of rungs when arriving on column `x`.
+pub fn next_column(rungs: &[bool], x: usize) -> usize {
+    let columns = rungs.len();
+    let left = (x + columns - 1) % columns;
+
+    // The last rung is only set on circular lines, so the first column only goes left through it
+    if rungs[x] {
+        (x + 1) % columns
+    } else if rungs[left] {
+        left
+    } else {
+        x
+    }
+}
+
+/// Moves every entry (state[entry] being its column) down a line of rungs.
+fn apply(rungs: &[bool], state: &[usize]) -> Vec<usize> {
+    state.iter().map(|x| next_column(rungs, *x)).collect()
+}
+
+/// Checks that the exit column of each entry column is a permutation of small enough size.
+fn check_permutation(permutation: &[usize]) -> Result<(), String> {
+    let mut seen = vec![false; permutation.len()];
+
+    if permutation.len() > MAX_COLUMNS {
+        return Err(format!(
+            "Ladders can only be counted up to {} columns",
+            MAX_COLUMNS
+        ));
+    }
+    for exit in permutation {
+        match seen.get_mut(*exit) {
+            Some(seen) if !*seen => *seen = true,
+            _ => return Err("Mapping is not a permutation of the columns".to_owned()),
+        }
+    }
+    Ok(())
+}
+
+/// Returns, for every state that can reach the permutation, the least number of lines needed.
+/// Lines being their own inverse, walking from the permutation gives the distances to it.
+fn distances(permutation: &[usize], rows: &[Vec<bool>]) -> HashMap<Vec<usize>, usize> {
+    let mut distances = HashMap::from([(permutation.to_vec(), 0)]);
+    let mut queue = VecDeque::from([permutation.to_vec()]);
+
+    while let Some(state) = queue.pop_front() {
+        let distance = distances[&state];
+
+        for rungs in rows {
+            let next = apply(rungs, &state);
+
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// Counts the ladders of the provided height whose entry column `x` exits in column `permutation[x]`.
+pub fn count(permutation: &[usize], height: usize, circular: bool) -> Result<u128, String> {
+    check_permutation(permutation)?;
+
+    let rows = rows(permutation.len(), circular);
+    let distances = distances(permutation, &rows);
+    let mut ways = HashMap::from([((0..permutation.len()).collect::<Vec<_>>(), 1u128)]);
+
+    for y in 0..height {
+        let mut next_ways = HashMap::new();
+
+        for (state, count) in &ways {
+            for rungs in &rows {
+                let next = apply(rungs, state);
+
+                // An empty line can always be added, only states too far from the permutation are dead ends
+                if distances.get(&next).is_some_and(|d| *d < height - y) {
+                    let total = next_ways.entry(next).or_insert(0u128);
+
+                    *total = total
+                        .checked_add(*count)
+                        .ok_or("Count of ladders does not fit in 128 bits")?;
+                }
+            }
+        }
+        ways = next_ways;
+    }
+    Ok(ways.get(permutation).copied().unwrap_or(0))
+}
+
+/// Lists lazily the ladders realising a permutation, line after line (cf. ladders).
+#[derive(Debug)]
+pub struct Ladders {
+    entries: Vec<String>,
+    exits: Vec<String>,
+    circular: bool,
+    height: usize,
+    rows: Vec<Vec<bool>>,
+    distances: HashMap<Vec<usize>, usize>,
+    /// For each line already chosen (and the next one), the state above it and the next row to try.
+    stack: Vec<(Vec<usize>, usize)>,
+    chosen: Vec<usize>,
+}
+
+impl Iterator for Ladders {
+    type Item = Map;
+
+    fn next(&mut self) -> Option<Map> {
+        loop {
+            let depth = self.stack.len().checked_sub(1)?;
+            let lines_left = self.height - depth;
+
+            if lines_left == 0 {
+                let rungs = self
+                    .chosen
+                    .iter()
+                    .map(|row| self.rows[*row].clone())
+                    .collect::<Vec<_>>();
+
+                self.stack.pop();
+                self.chosen.pop();
+                return Some(Map::from_rungs(
+                    self.entries.clone(),
+                    self.exits.clone(),
+                    &rungs,
+                    self.circular,
+                ));
+            }
+
+            let (state, next_row) = self.stack.last_mut()?;
+
+            match self.rows.get(*next_row) {
+                Some(rungs) => {
+                    let next = apply(rungs, state);
+
+                    *next_row += 1;
+                    if self.distances.get(&next).is_some_and(|d| *d < lines_left) {
+                        self.chosen.push(*next_row - 1);
+                        self.stack.push((next, 0));
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    self.chosen.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Lists lazily the ladders of the provided height whose entry column `x` exits in column `permutation[x]`.
+pub fn ladders(
+    entries: Vec<String>,
+    exits: Vec<String>,
+    permutation: &[usize],
+    height: usize,
+    circular: bool,
+) -> Result<Ladders, String> {
+    check_permutation(permutation)?;
+    if entries.len() != permutation.len() || exits.len() != permutation.len() {
+        return Err("Labels do not match the permutation".to_owned());
+    }
+
+    let rows = rows(permutation.len(), circular);
+    let distances = distances(permutation, &rows);
+    let start = (0..permutation.len()).collect::<Vec<_>>();
+    let stack = if distances.get(&start).is_some_and(|d| *d <= height) {
+        vec![(start, 0)]
+    } else {
+        vec![]
+    };
+
+    Ok(Ladders {
+        entries,
+        exits,
+        circular,
+        height,
+        rows,
+        distances,
+        stack,
+        chosen: vec![],
+    })
+}
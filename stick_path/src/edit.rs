@@ -0,0 +1,666 @@
+use crate::map::Map;
+
+/// A change of the map that can be applied, undone and redone.
+/// Rungs are identified by their line and the column on their left (the last column for the wrap rung).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    SetRung {
+        line: usize,
+        column: usize,
+        present: bool,
+    },
+    /// Replaces the characters of a line, used to undo edits keeping its exact characters.
+    ReplaceLine {
+        at: usize,
+        content: Vec<char>,
+    },
+    InsertLine {
+        at: usize,
+        content: Vec<char>,
+    },
+    DeleteLine {
+        at: usize,
+    },
+    MoveLine {
+        from: usize,
+        to: usize,
+    },
+    InsertColumn {
+        at: usize,
+        entry: String,
+        exit: String,
+    },
+    DeleteColumn {
+        at: usize,
+    },
+    MoveColumn {
+        from: usize,
+        to: usize,
+    },
+    /// Several edits applied in order, used to undo edits that removed rungs on their way.
+    Batch(Vec<Edit>),
+}
+
+/// Edits done on a map, each one stored with the edit undoing it.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<(Edit, Edit)>,
+    redo: Vec<(Edit, Edit)>,
+}
+
+impl Map {
+    /// Adds a rung in between the column and the column on its right.
+    pub fn add_rung(&mut self, line: usize, column: usize) -> Result<(), String> {
+        if self.rungs_for_edit(line)?.get(column) == Some(&true) {
+            return Err("Rung already exists".to_owned());
+        }
+        self.edit(Edit::SetRung {
+            line,
+            column,
+            present: true,
+        })
+    }
+
+    /// Removes the rung in between the column and the column on its right.
+    pub fn remove_rung(&mut self, line: usize, column: usize) -> Result<(), String> {
+        if self.rungs_for_edit(line)?.get(column) != Some(&true) {
+            return Err("Rung does not exist".to_owned());
+        }
+        self.edit(Edit::SetRung {
+            line,
+            column,
+            present: false,
+        })
+    }
+
+    /// Adds or removes the rung in between the column and the column on its right.
+    pub fn toggle_rung(&mut self, line: usize, column: usize) -> Result<(), String> {
+        let present = self.rungs_for_edit(line)?.get(column) == Some(&true);
+
+        self.edit(Edit::SetRung {
+            line,
+            column,
+            present: !present,
+        })
+    }
+
+    /// Inserts a line without any rung before the line `at` (or at the bottom if `at` is the height).
+    pub fn insert_line(&mut self, at: usize) -> Result<(), String> {
+        let content = Map::line_for_rungs(&vec![false; self.entries.len()], self.circular);
+
+        self.edit(Edit::InsertLine { at, content })
+    }
+
+    /// Deletes the line, with its rungs.
+    pub fn delete_line(&mut self, at: usize) -> Result<(), String> {
+        self.edit(Edit::DeleteLine { at })
+    }
+
+    /// Moves the line so that it ends up at the index `to`.
+    pub fn move_line(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.edit(Edit::MoveLine { from, to })
+    }
+
+    /// Inserts a column without any rung before the column `at` (or on the right if `at` is the width).
+    /// Rungs going over the new column are removed.
+    pub fn insert_column(&mut self, at: usize, entry: &str, exit: &str) -> Result<(), String> {
+        self.edit(Edit::InsertColumn {
+            at,
+            entry: entry.to_owned(),
+            exit: exit.to_owned(),
+        })
+    }
+
+    /// Deletes the column, its entry, its exit and the rungs linked to it.
+    pub fn delete_column(&mut self, at: usize) -> Result<(), String> {
+        self.edit(Edit::DeleteColumn { at })
+    }
+
+    /// Moves the column, its entry, its exit and its pole so that it ends up at the index `to`.
+    /// Rungs linked to it and the rung going over its new place are removed.
+    pub fn move_column(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.edit(Edit::MoveColumn { from, to })
+    }
+
+    /// Undoes the last edit. Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> Result<bool, String> {
+        match self.history.undo.pop() {
+            Some((edit, inverse)) => {
+                let result = self.apply(&inverse);
+
+                self.update_width();
+                // A failed undo is left in the history, the map being unchanged
+                match result {
+                    Ok(_) => self.history.redo.push((edit, inverse)),
+                    Err(_) => self.history.undo.push((edit, inverse)),
+                }
+                result.map(|_| true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Redoes the last undone edit. Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> Result<bool, String> {
+        match self.history.redo.pop() {
+            Some((edit, inverse)) => {
+                let result = self.apply(&edit);
+
+                self.update_width();
+                match result {
+                    Ok(_) => self.history.undo.push((edit, inverse)),
+                    Err(_) => self.history.redo.push((edit, inverse)),
+                }
+                result.map(|_| true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Returns the exit column of each entry column, obstacles being ignored.
+    /// Computed once, then updated by each edit instead of walking the whole map again
+    /// (changing `content` directly does not update it). Maps with portals can not be edited.
+    pub fn permutation(&mut self) -> Result<&[usize], String> {
+        if self.permutation.is_none() {
+            self.check_no_portals("Editing")?;
+
+            let permutation = (0..self.entries.len())
+                .map(|x| (0..self.height).try_fold(x, |x, y| self.next_column_for_coords(x, y)))
+                .collect::<Result<Vec<_>, String>>()?;
+
+            self.permutation = Some(permutation);
+        }
+        Ok(self.permutation.as_deref().unwrap_or_default())
+    }
+
+    /// Applies an edit and records it in the history, clearing the edits that could be redone.
+    /// A rejected edit leaves the map as it was.
+    pub fn edit(&mut self, edit: Edit) -> Result<(), String> {
+        let inverse = self.apply(&edit)?;
+
+        self.update_width();
+        self.history.undo.push((edit, inverse));
+        self.history.redo.clear();
+        Ok(())
+    }
+
+    /// Returns the rungs of a line, checking it can be edited.
+    fn rungs_for_edit(&self, line: usize) -> Result<Vec<bool>, String> {
+        let rungs = self.rungs_for_line(line)?;
+
+        if rungs.len() == self.entries.len() {
+            Ok(rungs)
+        } else {
+            Err(format!(
+                "Line {} does not have as many columns as entries",
+                line + 1
+            ))
+        }
+    }
+
+    /// Returns the slots of the rungs that can not coexist with a rung on the provided slot.
+    fn neighbour_slots(&self, column: usize) -> Vec<usize> {
+        let columns = self.entries.len();
+        let mut slots = vec![];
+
+        if column > 0 {
+            slots.push(column - 1);
+        } else if self.circular {
+            slots.push(columns - 1);
+        }
+        if column + 1 < columns {
+            slots.push(column + 1);
+        } else if self.circular {
+            slots.push(0);
+        }
+        slots
+    }
+
+    /// Applies an edit, keeping the permutation up to date. Returns the edit undoing it.
+    /// An edit failing partway undoes what it already changed before returning the error.
+    fn apply(&mut self, edit: &Edit) -> Result<Edit, String> {
+        match edit {
+            Edit::SetRung {
+                line,
+                column,
+                present,
+            } => {
+                let content = self.line_for_edit(*line)?;
+
+                self.apply_set_rung(*line, *column, *present)?;
+                Ok(Edit::ReplaceLine { at: *line, content })
+            }
+            Edit::ReplaceLine { at, content } => self.apply_replace_line(*at, content),
+            Edit::InsertLine { at, content } => self.apply_insert_line(*at, content),
+            Edit::DeleteLine { at } => self.apply_delete_line(*at),
+            Edit::MoveLine { from, to } => {
+                if *from >= self.height || *to >= self.height {
+                    return Err("Map has not enough lines for the required coordinates".to_owned());
+                }
+                if let Edit::InsertLine { content, .. } = self.apply_delete_line(*from)? {
+                    if let Err(err) = self.apply_insert_line(*to, &content) {
+                        self.roll_back(vec![Edit::InsertLine { at: *from, content }]);
+                        return Err(err);
+                    }
+                }
+                Ok(Edit::MoveLine {
+                    from: *to,
+                    to: *from,
+                })
+            }
+            Edit::InsertColumn { at, entry, exit } => self.apply_insert_column(*at, entry, exit),
+            Edit::DeleteColumn { at } => self.apply_delete_column(*at),
+            Edit::MoveColumn { from, to } => self.apply_move_column(*from, *to),
+            Edit::Batch(edits) => {
+                let mut inverses = vec![];
+
+                for edit in edits {
+                    match self.apply(edit) {
+                        Ok(inverse) => inverses.push(inverse),
+                        Err(err) => {
+                            self.roll_back(inverses);
+                            return Err(err);
+                        }
+                    }
+                }
+                inverses.reverse();
+                Ok(Edit::Batch(inverses))
+            }
+        }
+    }
+
+    /// Applies the inverses of the edits already applied by a failing edit, the last one first.
+    fn roll_back(&mut self, inverses: Vec<Edit>) {
+        for inverse in inverses.iter().rev() {
+            // Edits that were just applied can always be undone
+            let _ = self.apply(inverse);
+        }
+    }
+
+    /// Returns a copy of the characters of a line.
+    fn line_for_edit(&self, line: usize) -> Result<Vec<char>, String> {
+        self.content
+            .get(line)
+            .cloned()
+            .ok_or("Map has not enough lines for the required coordinates".to_owned())
+    }
+
+    /// Returns the content coordinates of the poles of a line.
+    fn poles(&self, line: usize) -> Vec<usize> {
+        self.content.get(line).map_or(vec![], |content| {
+            content
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| Map::is_pole(c))
+                .map(|(i, _)| i)
+                .collect()
+        })
+    }
+
+    /// Adds or removes a rung. Toggling a rung swaps the exits of the two entries arriving on
+    /// its columns, found by walking back up from the rung.
+    fn apply_set_rung(&mut self, line: usize, column: usize, present: bool) -> Result<(), String> {
+        let rungs = self.rungs_for_edit(line)?;
+        let columns = rungs.len();
+
+        if column >= columns || (column + 1 == columns && !(self.circular && columns > 1)) {
+            return Err("No rung can go right of this column".to_owned());
+        }
+        if present
+            && self
+                .neighbour_slots(column)
+                .iter()
+                .any(|slot| *slot != column && rungs[*slot])
+        {
+            return Err(format!(
+                "Rung on line {} right of column {} would conflict with another rung",
+                line + 1,
+                column + 1
+            ));
+        }
+        if rungs[column] == present {
+            return Ok(());
+        }
+        self.permutation()?;
+
+        let mut sides = [column, (column + 1) % columns];
+
+        for side in sides.iter_mut() {
+            for y in (0..line).rev() {
+                *side = self.next_column_for_coords(*side, y)?;
+            }
+        }
+        if let Some(permutation) = self.permutation.as_mut() {
+            permutation.swap(sides[0], sides[1]);
+        }
+        self.write_rung(line, column, present);
+        Ok(())
+    }
+
+    /// Writes or erases a rung (with its weight). A rung in between two poles is written `--` as in
+    /// line_for_rungs, a wrap rung as a '-' on both edges of the line.
+    fn write_rung(&mut self, line: usize, column: usize, present: bool) {
+        let poles = self.poles(line);
+        let weighted = self
+            .rung_segment(column, line)
+            .is_ok_and(|segment| Map::weight_in_segment(&segment).is_some());
+        let content = &mut self.content[line];
+
+        if column + 1 < poles.len() {
+            let rung = if present { vec!['-', '-'] } else { vec![] };
+
+            content.splice(poles[column] + 1..poles[column + 1], rung);
+            return;
+        }
+        if !present && weighted {
+            content.truncate(poles[column] + 1);
+            content.drain(..poles[0]);
+            return;
+        }
+
+        // Both edges are checked by the walkers, the right one is edited first so that the left one does not move
+        set_dash_after(content, poles[column], present);
+        set_dash_before(content, poles[0], present);
+    }
+
+    /// Replaces the characters of a line, changing its rungs one by one (removed ones first so
+    /// that they never conflict with the added ones).
+    fn apply_replace_line(&mut self, at: usize, content: &[char]) -> Result<Edit, String> {
+        let previous = self.line_for_edit(at)?;
+        let current = self.rungs_for_edit(at)?;
+
+        self.content[at] = content.to_vec();
+
+        let target = self.rungs_for_edit(at);
+
+        self.content[at] = previous.clone();
+
+        let target = target?;
+        let mut changed: Vec<(usize, bool)> = vec![];
+
+        for present in [false, true] {
+            for column in 0..target.len() {
+                if target[column] == present && current[column] != present {
+                    if let Err(err) = self.apply_set_rung(at, column, present) {
+                        for (column, present) in changed.into_iter().rev() {
+                            let _ = self.apply_set_rung(at, column, !present);
+                        }
+                        self.content[at] = previous;
+                        return Err(err);
+                    }
+                    changed.push((column, present));
+                }
+            }
+        }
+        // Gives back the exact characters of the line (its rungs being the same)
+        self.content[at] = content.to_vec();
+        Ok(Edit::ReplaceLine {
+            at,
+            content: previous,
+        })
+    }
+
+    /// Inserts a line: a line without rungs first, then its rungs one by one.
+    fn apply_insert_line(&mut self, at: usize, content: &[char]) -> Result<Edit, String> {
+        if at > self.height {
+            return Err("Map has not enough lines for the required coordinates".to_owned());
+        }
+        self.permutation()?;
+        self.content.insert(
+            at,
+            Map::line_for_rungs(&vec![false; self.entries.len()], self.circular),
+        );
+        self.height += 1;
+        self.shift_layers(at, true);
+        if let Err(err) = self.apply_replace_line(at, content) {
+            self.content.remove(at);
+            self.height -= 1;
+            self.shift_layers(at, false);
+            return Err(err);
+        }
+        Ok(Edit::DeleteLine { at })
+    }
+
+    /// Deletes a line: its rungs one by one first, then the line without rungs.
+    fn apply_delete_line(&mut self, at: usize) -> Result<Edit, String> {
+        let content = self.line_for_edit(at)?;
+        let empty = Map::line_for_rungs(&vec![false; self.entries.len()], self.circular);
+
+        // An empty layer could not be told apart from the next one when the line is put back
+        if let Some(layer) = self.layer_for_line(at) {
+            if self.layers[layer].start == at && self.layer_end(layer) == at + 1 {
+                return Err(format!(
+                    "Line {} is the only line of layer {}",
+                    at + 1,
+                    self.layers[layer].name
+                ));
+            }
+        }
+        self.apply_replace_line(at, &empty)?;
+        self.content.remove(at);
+        self.height -= 1;
+        self.shift_layers(at, false);
+        Ok(Edit::InsertLine { at, content })
+    }
+
+    /// Moves the layers starting after the line inserted or deleted at `at`.
+    /// A line inserted at the start of a layer belongs to it.
+    fn shift_layers(&mut self, at: usize, inserted: bool) {
+        for layer in self.layers.iter_mut().filter(|layer| layer.start > at) {
+            if inserted {
+                layer.start += 1;
+            } else {
+                layer.start -= 1;
+            }
+        }
+    }
+
+    /// Removes the rungs of the provided slots on every line, returning the edits putting them back.
+    fn remove_rungs_on_slots(&mut self, slots: &[usize]) -> Result<Vec<Edit>, String> {
+        let mut restore = vec![];
+
+        for line in 0..self.height {
+            if let Err(err) = self.remove_rungs_on_line(line, slots, &mut restore) {
+                self.roll_back(restore);
+                return Err(err);
+            }
+        }
+        Ok(restore)
+    }
+
+    /// Removes the rungs of the provided slots on a line, adding the edit putting them back to `restore`
+    /// before changing anything.
+    fn remove_rungs_on_line(
+        &mut self,
+        line: usize,
+        slots: &[usize],
+        restore: &mut Vec<Edit>,
+    ) -> Result<(), String> {
+        let content = self.line_for_edit(line)?;
+        let rungs = self.rungs_for_edit(line)?;
+
+        if slots.iter().any(|slot| rungs.get(*slot) == Some(&true)) {
+            restore.push(Edit::ReplaceLine { at: line, content });
+            for slot in slots.iter().filter(|slot| rungs.get(**slot) == Some(&true)) {
+                self.apply_set_rung(line, *slot, false)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps the width of the map in line with its content after an edit, so that it is still read back once rendered.
+    fn update_width(&mut self) {
+        self.width = self.rendered_width();
+    }
+
+    /// Inserts a column. The rung going over the new column is removed first, then the column is
+    /// added as a column ending in itself so the permutation only has to be shifted.
+    fn apply_insert_column(&mut self, at: usize, entry: &str, exit: &str) -> Result<Edit, String> {
+        let columns = self.entries.len();
+
+        if at > columns {
+            return Err("Map has not enough columns for the required coordinates".to_owned());
+        }
+
+        let over = if at > 0 && at < columns {
+            vec![at - 1]
+        } else if self.circular && columns > 1 {
+            vec![columns - 1]
+        } else {
+            vec![]
+        };
+
+        self.permutation()?;
+
+        let restore = self.remove_rungs_on_slots(&over)?;
+
+        for line in 0..self.height {
+            let poles = self.poles(line);
+            let index = match at {
+                0 => poles.first().copied().unwrap_or(0),
+                _ => poles[at - 1] + 1,
+            };
+
+            self.content[line].insert(index, '|');
+        }
+        if let Some(permutation) = self.permutation.as_mut() {
+            for exit in permutation.iter_mut() {
+                if *exit >= at {
+                    *exit += 1;
+                }
+            }
+            permutation.insert(at, at);
+        }
+        self.entries.insert(at, entry.to_owned());
+        self.exits.insert(at, exit.to_owned());
+
+        let mut undo = vec![Edit::DeleteColumn { at }];
+
+        undo.extend(restore);
+        Ok(Edit::Batch(undo))
+    }
+
+    /// Deletes a column. Its rungs are removed first so it ends in itself and the permutation
+    /// only has to be shifted.
+    fn apply_delete_column(&mut self, at: usize) -> Result<Edit, String> {
+        let columns = self.entries.len();
+
+        if at >= columns {
+            return Err("Map has not enough columns for the required coordinates".to_owned());
+        }
+
+        self.permutation()?;
+
+        let left = if at > 0 { at - 1 } else { columns - 1 };
+        let restore = self.remove_rungs_on_slots(&[left, at])?;
+
+        for line in 0..self.height {
+            let index = self.poles(line)[at];
+
+            self.content[line].remove(index);
+        }
+        if let Some(permutation) = self.permutation.as_mut() {
+            permutation.remove(at);
+            for exit in permutation.iter_mut() {
+                if *exit > at {
+                    *exit -= 1;
+                }
+            }
+        }
+
+        let entry = self.entries.remove(at);
+        let exit = self.exits.remove(at);
+        let mut undo = vec![Edit::InsertColumn { at, entry, exit }];
+
+        undo.extend(restore);
+        Ok(Edit::Batch(undo))
+    }
+
+    /// Moves a column with its pole (obstacles included). Its rungs and the rung going over its new
+    /// place are removed first so it ends in itself and the permutation only has to be shifted.
+    fn apply_move_column(&mut self, from: usize, to: usize) -> Result<Edit, String> {
+        let columns = self.entries.len();
+
+        if from >= columns || to >= columns {
+            return Err("Map has not enough columns for the required coordinates".to_owned());
+        }
+        if from == to {
+            return Ok(Edit::MoveColumn { from, to });
+        }
+        self.permutation()?;
+
+        // Slots are numbered before the move, the new place being right of the column `to` when
+        // moving right and left of it when moving left
+        let left = (from + columns - 1) % columns;
+        let over = if to > from {
+            to
+        } else {
+            (to + columns - 1) % columns
+        };
+        let restore = self.remove_rungs_on_slots(&[left, from, over])?;
+
+        for line in 0..self.height {
+            let index = self.poles(line)[from];
+            let pole = self.content[line].remove(index);
+            let poles = self.poles(line);
+            let index = match to {
+                0 => poles.first().copied().unwrap_or(0),
+                _ => poles[to - 1] + 1,
+            };
+
+            self.content[line].insert(index, pole);
+        }
+        if let Some(permutation) = self.permutation.as_mut() {
+            permutation.remove(from);
+            for exit in permutation.iter_mut() {
+                if *exit > from {
+                    *exit -= 1;
+                }
+                if *exit >= to {
+                    *exit += 1;
+                }
+            }
+            permutation.insert(to, to);
+        }
+
+        let entry = self.entries.remove(from);
+        let exit = self.exits.remove(from);
+
+        self.entries.insert(to, entry);
+        self.exits.insert(to, exit);
+
+        let mut undo = vec![Edit::MoveColumn { from: to, to: from }];
+
+        undo.extend(restore);
+        Ok(Edit::Batch(undo))
+    }
+}
+
+/// Writes or erases the '-' on the right of a pole.
+fn set_dash_after(content: &mut Vec<char>, pole: usize, present: bool) {
+    if present {
+        if content.get(pole + 1) != Some(&'-') {
+            content.insert(pole + 1, '-');
+        }
+    } else {
+        while content.get(pole + 1) == Some(&'-') {
+            content.remove(pole + 1);
+        }
+    }
+}
+
+/// Writes or erases the '-' on the left of a pole.
+fn set_dash_before(content: &mut Vec<char>, pole: usize, present: bool) {
+    if present {
+        if pole == 0 || content[pole - 1] != '-' {
+            content.insert(pole, '-');
+        }
+    } else {
+        let mut pole = pole;
+
+        while pole > 0 && content[pole - 1] == '-' {
+            content.remove(pole - 1);
+            pole -= 1;
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::json;
+use crate::stream::ReadError;
+
+/// Kinds of failures of the command line, each one exiting with its own code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Unknown command, missing or invalid argument.
+    Usage,
+    /// The input (or the terminal) could not be read or written.
+    Io,
+    /// The input is not a map.
+    Parse,
+    /// The map breaks the rules (cf. Map::validate).
+    Validation,
+    /// The map is valid but the command could not give an answer.
+    Solve,
+}
+
+impl ErrorKind {
+    /// Returns the code the program exits with. 1 is left to `check` reporting mismatches.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Usage => 2,
+            ErrorKind::Io => 3,
+            ErrorKind::Parse => 4,
+            ErrorKind::Validation => 5,
+            ErrorKind::Solve => 6,
+        }
+    }
+
+    /// Returns the name of the kind in the JSON error format.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Usage => "usage",
+            ErrorKind::Io => "io",
+            ErrorKind::Parse => "parse",
+            ErrorKind::Validation => "validation",
+            ErrorKind::Solve => "solve",
+        }
+    }
+}
+
+/// Failure of the command line, with the 1-based index of its map when several maps are read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub map: Option<usize>,
+}
+
+impl CliError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> CliError {
+        CliError {
+            kind,
+            message: message.into(),
+            map: None,
+        }
+    }
+
+    pub fn usage(message: impl Into<String>) -> CliError {
+        CliError::new(ErrorKind::Usage, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> CliError {
+        CliError::new(ErrorKind::Io, message)
+    }
+
+    pub fn parse(message: impl Into<String>) -> CliError {
+        CliError::new(ErrorKind::Parse, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> CliError {
+        CliError::new(ErrorKind::Validation, message)
+    }
+
+    pub fn solve(message: impl Into<String>) -> CliError {
+        CliError::new(ErrorKind::Solve, message)
+    }
+
+    /// Sets the index of the map the error comes from.
+    pub fn for_map(self, map: usize) -> CliError {
+        CliError {
+            map: Some(map),
+            ..self
+        }
+    }
+
+    /// Formats the error as a single JSON line.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"kind\":\"{}\",\"exit_code\":{},{}\"message\":{}}}",
+            self.kind.name(),
+            self.kind.exit_code(),
+            self.map
+                .map(|map| format!("\"map\":{},", map))
+                .unwrap_or_default(),
+            json::string(&self.message)
+        )
+    }
+
+    /// Formats the error as expected on the standard error.
+    pub fn render(&self, format: ErrorFormat) -> String {
+        match format {
+            ErrorFormat::Text => self.to_string(),
+            ErrorFormat::Json => self.to_json(),
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(map) = self.map {
+            write!(f, "Map {}: ", map)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<ReadError> for CliError {
+    fn from(err: ReadError) -> CliError {
+        match err {
+            ReadError::Io(message) => CliError::io(message),
+            ReadError::Parse(message) => CliError::parse(message),
+        }
+    }
+}
+
+/// Formats errors can be written in on the standard error (`--error-format text|json`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for ErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ErrorFormat, String> {
+        match s {
+            "text" => Ok(ErrorFormat::Text),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(format!("Unknown error format {}", s)),
+        }
+    }
+}
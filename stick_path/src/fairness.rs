@@ -0,0 +1,277 @@
+use std::fmt;
+
+use crate::generator::{self, Rng};
+
+/// Total variation distance from uniform under which a ladder is considered well mixed.
+pub const MIXING_TOLERANCE: f64 = 0.001;
+
+/// Height above which we stop looking for a well mixed ladder.
+pub const MAX_RECOMMENDED_HEIGHT: usize = 1 << 13;
+
+/// Width above which no ladder mixes within `MAX_RECOMMENDED_HEIGHT` lines: a walker moving at most one
+/// column per line has a relaxation time of at least c²/2π² lines, about 3300 for 256 columns,
+/// so reaching `MIXING_TOLERANCE` takes more than 6 times that.
+const MAX_MIXING_COLUMNS: usize = 256;
+
+/// Statistics of the exits reached by each entry over many random ladders.
+#[derive(Debug)]
+pub struct FairnessReport {
+    pub columns: usize,
+    pub height: usize,
+    pub density: f64,
+    pub trials: usize,
+    /// `counts[entry][exit]` is the number of ladders in which the entry reached the exit.
+    pub counts: Vec<Vec<usize>>,
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+    /// Value the chi-square statistic stays under 95% of the time for fair ladders.
+    pub critical_value: f64,
+    /// Largest gap between an observed probability and the uniform one.
+    pub max_deviation: f64,
+    pub recommended_height: Option<usize>,
+}
+
+impl FairnessReport {
+    /// Tells you if the observed exits are compatible with a uniform draw (at a 95% confidence level).
+    pub fn looks_uniform(&self) -> bool {
+        self.chi_square <= self.critical_value
+    }
+}
+
+impl fmt::Display for FairnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Exits reached over {} ladders ({} columns, {} lines, density {}):",
+            self.trials, self.columns, self.height, self.density
+        )?;
+        write!(f, "{:>4}", "")?;
+        for x in 0..self.columns {
+            write!(f, " {:>7}", generator::exit_label(x))?;
+        }
+        writeln!(f)?;
+        for (x, counts) in self.counts.iter().enumerate() {
+            write!(f, "{:>4}", generator::entry_label(x))?;
+            for count in counts {
+                write!(f, " {:>7}", count)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(
+            f,
+            "Chi-square: {:.2} ({} degrees of freedom, 95% critical value {:.2}) => {}",
+            self.chi_square,
+            self.degrees_of_freedom,
+            self.critical_value,
+            if self.looks_uniform() {
+                "looks uniform"
+            } else {
+                "not uniform"
+            }
+        )?;
+        writeln!(
+            f,
+            "Largest deviation from uniform: {:.2}%",
+            self.max_deviation * 100.0
+        )?;
+        match self.recommended_height {
+            Some(height) => writeln!(f, "Recommended minimum height: {} lines", height),
+            None => writeln!(
+                f,
+                "Recommended minimum height: not reachable within {} lines",
+                MAX_RECOMMENDED_HEIGHT
+            ),
+        }
+    }
+}
+
+/// Approximates the chi-square value exceeded with a 5% probability (Wilson-Hilferty).
+pub fn chi_square_critical_value(degrees_of_freedom: usize) -> f64 {
+    let k = degrees_of_freedom as f64;
+    let z = 1.644_854;
+
+    if k == 0.0 {
+        0.0
+    } else {
+        k * (1.0 - 2.0 / (9.0 * k) + z * (2.0 / (9.0 * k)).sqrt()).powi(3)
+    }
+}
+
+/// Returns the probability of each rung slot to hold a rung with `generator::random_rungs`.
+pub fn rung_probabilities(columns: usize, density: f64, circular: bool) -> Vec<f64> {
+    let slots = if circular {
+        columns
+    } else {
+        columns.saturating_sub(1)
+    };
+    let mut probabilities = vec![0.0; columns];
+    // Probability of each (first slot has a rung, previous slot has a rung) state
+    let mut states = [[0.0; 2]; 2];
+
+    states[0][0] = 1.0;
+    for (slot, probability) in probabilities.iter_mut().enumerate().take(slots) {
+        let mut next = [[0.0; 2]; 2];
+
+        for (first, previous_states) in states.iter().enumerate() {
+            for (previous, p) in previous_states.iter().enumerate() {
+                let free = (slot == 0 || previous == 0) && (slot + 1 < columns || first == 0);
+
+                if free {
+                    let first_after = if slot == 0 { 1 } else { first };
+
+                    *probability += p * density;
+                    next[first_after][1] += p * density;
+                    next[first][0] += p * (1.0 - density);
+                } else {
+                    next[first][0] += p;
+                }
+            }
+        }
+        states = next;
+    }
+    probabilities
+}
+
+/// Returns the matrix of the probabilities for a walker on a column (row) to be on each column (column)
+/// after `a` lines then `b` lines.
+fn multiply(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    a.iter()
+        .map(|row| {
+            let mut product = vec![0.0; b.len()];
+
+            for (p, next) in row.iter().zip(b) {
+                if *p != 0.0 {
+                    for (product, q) in product.iter_mut().zip(next) {
+                        *product += p * q;
+                    }
+                }
+            }
+            product
+        })
+        .collect()
+}
+
+/// Returns the largest total variation distance from uniform over the entries (rows).
+fn distance_from_uniform(matrix: &[Vec<f64>]) -> f64 {
+    let uniform = 1.0 / matrix.len() as f64;
+
+    matrix
+        .iter()
+        .map(|row| row.iter().map(|p| (p - uniform).abs()).sum::<f64>() / 2.0)
+        .fold(0.0, f64::max)
+}
+
+/// Returns the height from which every entry reaches every exit with a probability close to uniform,
+/// `None` if that takes more than `MAX_RECOMMENDED_HEIGHT` lines (or never happens).
+/// The position of a single walker only depends on the probability of each slot to hold a rung,
+/// so its distribution after `h` lines is the `h`-th power of the matrix of a single line.
+/// Lines are doubly stochastic, so the distance from uniform never grows with the height:
+/// the height is bracketed by doubling it, then found by binary search.
+pub fn recommended_height(columns: usize, density: f64, circular: bool) -> Option<usize> {
+    let probabilities = rung_probabilities(columns, density, circular);
+
+    if !(2..=MAX_MIXING_COLUMNS).contains(&columns) || probabilities.iter().all(|p| *p == 0.0) {
+        return None;
+    }
+
+    let mut line = vec![vec![0.0; columns]; columns];
+
+    for x in 0..columns {
+        let right = probabilities[x];
+        let left = probabilities[(x + columns - 1) % columns];
+
+        line[x][x] += 1.0 - left - right;
+        line[x][(x + 1) % columns] += right;
+        line[x][(x + columns - 1) % columns] += left;
+    }
+
+    // powers[k] is the matrix of 2^k lines
+    let mut powers = vec![line];
+
+    while distance_from_uniform(&powers[powers.len() - 1]) > MIXING_TOLERANCE {
+        if 1 << powers.len() > MAX_RECOMMENDED_HEIGHT {
+            return None;
+        }
+
+        let last = &powers[powers.len() - 1];
+
+        powers.push(multiply(last, last));
+    }
+
+    // Highest height known not to be mixed, with its matrix (none for 0 lines)
+    let mut height = 0;
+    let mut matrix: Option<Vec<Vec<f64>>> = None;
+
+    for (k, power) in powers.iter().enumerate().rev().skip(1) {
+        let candidate = match &matrix {
+            Some(matrix) => multiply(matrix, power),
+            None => power.clone(),
+        };
+
+        if distance_from_uniform(&candidate) > MIXING_TOLERANCE {
+            height += 1 << k;
+            matrix = Some(candidate);
+        }
+    }
+    Some(height + 1)
+}
+
+/// Generates `trials` random ladders and compares the exits reached by each entry to a uniform draw.
+pub fn analyse(
+    columns: usize,
+    height: usize,
+    density: f64,
+    circular: bool,
+    trials: usize,
+    rng: &mut Rng,
+) -> Result<FairnessReport, String> {
+    if columns < 2 {
+        return Err("Fairness needs at least 2 columns".to_owned());
+    }
+    if !(0.0..=1.0).contains(&density) {
+        return Err("Density must be in between 0 and 1".to_owned());
+    }
+    if trials == 0 {
+        return Err("Fairness needs at least one trial".to_owned());
+    }
+
+    let mut counts = vec![vec![0; columns]; columns];
+
+    for _ in 0..trials {
+        let map = generator::generate(columns, height, density, circular, rng);
+
+        for (x, entry) in map.entries.iter().enumerate() {
+            let trace = map.trace_for_entry(entry)?;
+
+            counts[x][trace[trace.len() - 1]] += 1;
+        }
+    }
+
+    let expected = trials as f64 / columns as f64;
+    let chi_square = counts
+        .iter()
+        .flatten()
+        .map(|count| (*count as f64 - expected).powi(2) / expected)
+        .sum();
+    let max_deviation = counts
+        .iter()
+        .flatten()
+        .map(|count| (*count as f64 / trials as f64 - 1.0 / columns as f64).abs())
+        .fold(0.0, f64::max);
+    // Each entry is compared to its own uniform distribution: the permutations tie the exits of
+    // the entries together, which leaves the mean of the statistic at columns * (columns - 1)
+    let degrees_of_freedom = columns * (columns - 1);
+
+    Ok(FairnessReport {
+        columns,
+        height,
+        density,
+        trials,
+        counts,
+        chi_square,
+        degrees_of_freedom,
+        critical_value: chi_square_critical_value(degrees_of_freedom),
+        max_deviation,
+        recommended_height: recommended_height(columns, density, circular),
+    })
+}
@@ -0,0 +1,235 @@
+use crate::count::next_column;
+use crate::map::Map;
+
+/// Character filling a rung segment (`|??|`) where the rung is left to the player: rung or no rung.
+/// On circular maps, an unknown wrap rung is written on both edges (`?| | |?`).
+pub const UNKNOWN: char = '?';
+
+impl Map {
+    /// Tells you if any rung of the map is unknown.
+    pub fn has_unknowns(&self) -> bool {
+        self.content.iter().any(|line| line.contains(&UNKNOWN))
+    }
+
+    /// Returns, for each column of the line, if the rung on its right is unknown (cf. rungs_for_line).
+    pub fn unknown_rungs_for_line(&self, y: usize) -> Result<Vec<bool>, String> {
+        let columns = self.column_count(y)?;
+        let line = &self.content[y];
+
+        (0..columns)
+            .map(|x| {
+                let segment = self.rung_segment(x, y)?;
+
+                if !segment.contains(&UNKNOWN) {
+                    return Ok(false);
+                }
+                if segment.iter().any(|c| c != &UNKNOWN) {
+                    return Err(format!(
+                        "Line {} mixes an unknown rung with other characters right of column {}",
+                        y + 1,
+                        x + 1
+                    ));
+                }
+                if x + 1 == columns {
+                    let first = line.iter().position(Map::is_pole).unwrap_or(0);
+                    let last = line.iter().rposition(Map::is_pole).unwrap_or(0);
+
+                    if !self.circular || first == 0 || last + 1 == line.len() {
+                        return Err(format!(
+                            "Line {} has an unknown rung leading nowhere right of column {}",
+                            y + 1,
+                            x + 1
+                        ));
+                    }
+                }
+                Ok(true)
+            })
+            .collect()
+    }
+
+    /// Returns a copy of the map with its unknown rungs replaced, `rungs[y]` being the rungs of the line `y`
+    /// (cf. rungs_for_line). Only the unknown rungs are read.
+    pub fn fill_unknowns(&self, rungs: &[Vec<bool>]) -> Result<Map, String> {
+        let mut map = self.clone();
+
+        for (y, line) in map.content.iter_mut().enumerate() {
+            let columns = line.iter().filter(|c| Map::is_pole(c)).count();
+            let mut filled = vec![];
+            let mut poles = 0;
+
+            for (i, c) in line.iter().enumerate() {
+                if Map::is_pole(c) {
+                    poles += 1;
+                } else if c == &UNKNOWN {
+                    // Segments are filled on their first character only
+                    if i > 0 && line[i - 1] == UNKNOWN {
+                        continue;
+                    }
+
+                    let edge = poles == 0 || poles == columns;
+                    let x = if edge {
+                        columns.saturating_sub(1)
+                    } else {
+                        poles - 1
+                    };
+                    let rung = rungs
+                        .get(y)
+                        .and_then(|rungs| rungs.get(x))
+                        .ok_or("Not enough rungs to fill the map")?;
+
+                    if *rung {
+                        filled.extend(if edge { vec!['-'] } else { vec!['-', '-'] });
+                    }
+                    continue;
+                }
+                filled.push(*c);
+            }
+            *line = filled;
+        }
+        map.permutation = None;
+        Ok(map)
+    }
+}
+
+/// Lines of rungs (cf. rungs_for_line) that can be written on each line of the map,
+/// keeping its known rungs and choosing every unknown one, no column being linked on both sides.
+fn candidate_rows(map: &Map) -> Result<Vec<Vec<Vec<bool>>>, String> {
+    let columns = map.entries.len();
+
+    (0..map.height)
+        .map(|y| {
+            let known = map.rungs_for_line(y)?;
+            let unknown = map.unknown_rungs_for_line(y)?;
+            let mut rows = vec![known];
+
+            for x in (0..columns).filter(|x| unknown[*x]) {
+                for i in 0..rows.len() {
+                    let mut row = rows[i].clone();
+
+                    row[x] = true;
+                    rows.push(row);
+                }
+            }
+            rows.retain(|row| (0..columns).all(|x| !(row[x] && row[(x + 1) % columns])));
+            Ok(rows)
+        })
+        .collect()
+}
+
+/// Returns, for each line and each column, the exit columns that can be reached from the top of the line,
+/// any unknown rung below being either a rung or not.
+fn reachable_exits(map: &Map, rows: &[Vec<Vec<bool>>]) -> Result<Vec<Vec<Vec<bool>>>, String> {
+    let columns = map.entries.len();
+    let mut reachable = vec![vec![vec![false; columns]; columns]; map.height + 1];
+
+    for (x, exits) in reachable[map.height].iter_mut().enumerate() {
+        exits[x] = true;
+    }
+    for y in (0..map.height).rev() {
+        for x in 0..columns {
+            for row in &rows[y] {
+                let next = next_column(row, x);
+
+                if !map.is_blocked(next, y)? {
+                    let below = reachable[y + 1][next].clone();
+
+                    for (exit, reached) in reachable[y][x].iter_mut().enumerate() {
+                        *reached |= below[exit];
+                    }
+                }
+            }
+        }
+    }
+    Ok(reachable)
+}
+
+/// Finds every way of filling the unknown rungs so that each expected entry reaches its expected exit,
+/// the other entries going anywhere. Returns the filled maps, none if there is no way.
+/// Lines are chosen from top to bottom, dropping any choice leaving an expected exit out of reach of its entry.
+pub fn fill(map: &Map, expected: &[(String, String)]) -> Result<Vec<Map>, String> {
+    map.check_no_portals("Fill")?;
+
+    let columns = map.entries.len();
+
+    if (0..map.height).any(|y| map.column_count(y).ok() != Some(columns)) {
+        return Err("Fill needs every line to have one column per entry".to_owned());
+    }
+
+    let rows = candidate_rows(map)?;
+
+    // Known rungs must be valid on their own, which they are when every unknown one is left out
+    map.fill_unknowns(&vec![vec![false; columns]; map.height])?
+        .validate()?;
+
+    let mut targets = vec![None; columns];
+
+    for (entry, exit) in expected {
+        let (x, _) = map.starting_coords_for_entry(entry)?;
+        let exit = map
+            .exits
+            .iter()
+            .position(|other| other == exit)
+            .ok_or(format!("Exit {} does not exist", exit))?;
+
+        targets[x] = Some(exit);
+    }
+
+    let reachable = reachable_exits(map, &rows)?;
+    let mut completions = vec![];
+    let mut chosen = vec![];
+
+    search(
+        map,
+        &rows,
+        &reachable,
+        &targets,
+        (0..columns).map(Some).collect(),
+        &mut chosen,
+        &mut completions,
+    )?;
+    Ok(completions)
+}
+
+/// Tries every row of the next line, `positions` being the column of each entry (`None` once trapped).
+fn search(
+    map: &Map,
+    rows: &[Vec<Vec<bool>>],
+    reachable: &[Vec<Vec<bool>>],
+    targets: &[Option<usize>],
+    positions: Vec<Option<usize>>,
+    chosen: &mut Vec<Vec<bool>>,
+    completions: &mut Vec<Map>,
+) -> Result<(), String> {
+    let y = chosen.len();
+
+    if y == map.height {
+        completions.push(map.fill_unknowns(chosen)?);
+        return Ok(());
+    }
+    for row in &rows[y] {
+        let next = positions
+            .iter()
+            .map(|position| {
+                position
+                    .map(|x| {
+                        let next = next_column(row, x);
+
+                        Ok((!map.is_blocked(next, y)?).then_some(next))
+                    })
+                    .transpose()
+                    .map(Option::flatten)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let possible = targets.iter().zip(&next).all(|(target, x)| match target {
+            Some(exit) => x.is_some_and(|x| reachable[y + 1][x][*exit]),
+            None => true,
+        });
+
+        if possible {
+            chosen.push(row.clone());
+            search(map, rows, reachable, targets, next, chosen, completions)?;
+            chosen.pop();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::map::Map;
+
+/// Small xorshift64* pseudo random generator, good enough for ladders and reproducible from a seed.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from a seed. A zero seed is replaced as xorshift would only return zeros.
+    pub fn new(seed: u64) -> Rng {
+        Rng {
+            state: if seed == 0 {
+                0x9E37_79B9_7F4A_7C15
+            } else {
+                seed
+            },
+        }
+    }
+
+    /// Creates a generator seeded from the current time.
+    pub fn from_time() -> Rng {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+
+        Rng::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a number in [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Returns the label of the entry at the provided index (A, B, ..., Z, AA, AB, ...).
+pub fn entry_label(mut i: usize) -> String {
+    let mut label = vec![];
+
+    loop {
+        label.push((b'A' + (i % 26) as u8) as char);
+        if i < 26 {
+            break;
+        }
+        i = i / 26 - 1;
+    }
+    label.iter().rev().collect()
+}
+
+/// Returns the label of the exit at the provided index (1, 2, 3, ...).
+pub fn exit_label(i: usize) -> String {
+    (i + 1).to_string()
+}
+
+/// Generates the rungs of a line, scanning from left to right and placing a rung with the provided
+/// probability wherever it does not conflict with the previous one (nor the first one for the wrap rung).
+/// A single column has no wrap rung, it would link the column to itself.
+pub fn random_rungs(columns: usize, density: f64, circular: bool, rng: &mut Rng) -> Vec<bool> {
+    let mut rungs = vec![false; columns];
+    let slots = if circular && columns > 1 {
+        columns
+    } else {
+        columns.saturating_sub(1)
+    };
+
+    for slot in 0..slots {
+        let free_left = slot == 0 || !rungs[slot - 1];
+        let free_right = slot + 1 < columns || !rungs[0];
+
+        rungs[slot] = free_left && free_right && rng.next_f64() < density;
+    }
+    rungs
+}
+
+/// Generates a random valid map.
+pub fn generate(columns: usize, height: usize, density: f64, circular: bool, rng: &mut Rng) -> Map {
+    let rungs = (0..height)
+        .map(|_| random_rungs(columns, density, circular, rng))
+        .collect::<Vec<_>>();
+
+    Map::from_rungs(
+        (0..columns).map(entry_label).collect(),
+        (0..columns).map(exit_label).collect(),
+        &rungs,
+        circular,
+    )
+}
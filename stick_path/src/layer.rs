@@ -0,0 +1,164 @@
+use std::fmt;
+
+use crate::map::Map;
+
+/// Character replacing a pole where it is a portal: walkers going down it are sent to the same
+/// column of the target layer (cf. Layer), on the line below the one they left.
+pub const PORTAL: char = 'o';
+
+/// Named part of a map, from its header line (`[name]` or `[name -> target]`) up to the next one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layer {
+    pub name: String,
+    /// Layer the portals of this layer lead to.
+    pub target: Option<String>,
+    /// First line of the layer in the map content.
+    pub start: usize,
+}
+
+impl fmt::Display for Layer {
+    /// Renders the header line of the layer.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(f, "[{} -> {}]", self.name, target),
+            None => write!(f, "[{}]", self.name),
+        }
+    }
+}
+
+impl Layer {
+    /// Tells you if the line is a layer header.
+    pub fn is_header(line: &str) -> bool {
+        let line = line.trim();
+
+        line.starts_with('[') && line.ends_with(']')
+    }
+
+    /// Parses a layer header starting on the provided line of the content.
+    pub fn from_header(header: &str, start: usize) -> Result<Layer, String> {
+        let inside = header
+            .trim()
+            .strip_prefix('[')
+            .and_then(|h| h.strip_suffix(']'))
+            .ok_or(format!("Invalid layer header {}", header.trim()))?;
+        let (name, target) = match inside.split_once("->") {
+            Some((name, target)) => (name.trim(), Some(target.trim())),
+            None => (inside.trim(), None),
+        };
+
+        if name.is_empty() || target.is_some_and(str::is_empty) {
+            return Err(format!("Invalid layer header {}", header.trim()));
+        }
+        Ok(Layer {
+            name: name.to_owned(),
+            target: target.map(|target| target.to_owned()),
+            start,
+        })
+    }
+}
+
+impl Map {
+    /// Splits the lines in between the entries and the exits into the map content and its layers.
+    /// Lines before the first header belong to no layer.
+    pub fn split_layers(lines: &[String]) -> Result<(Vec<Vec<char>>, Vec<Layer>), String> {
+        let mut content = vec![];
+        let mut layers: Vec<Layer> = vec![];
+
+        for line in lines {
+            if Layer::is_header(line) {
+                let layer = Layer::from_header(line, content.len())?;
+
+                if layers.iter().any(|other| other.name == layer.name) {
+                    return Err(format!("Layer {} is defined twice", layer.name));
+                }
+                layers.push(layer);
+            } else {
+                content.push(line.split(' ').collect::<String>().chars().collect());
+            }
+        }
+        Ok((content, layers))
+    }
+
+    /// Returns the index of the layer holding the provided line, if any.
+    pub fn layer_for_line(&self, y: usize) -> Option<usize> {
+        self.layers.iter().rposition(|layer| layer.start <= y)
+    }
+
+    /// Returns the line right after the last line of the layer.
+    pub fn layer_end(&self, layer: usize) -> usize {
+        self.layers
+            .get(layer + 1)
+            .map_or(self.height, |next| next.start)
+    }
+
+    /// Tells you if any pole of the map is a portal.
+    pub fn has_portals(&self) -> bool {
+        self.content.iter().any(|line| line.contains(&PORTAL))
+    }
+
+    /// Tells you if the column is a portal on the provided line.
+    pub fn is_portal(&self, x: usize, y: usize) -> Result<bool, String> {
+        let (content_x, content_y) = self.content_coords_for_coords(x, y)?;
+
+        Ok(self.content[content_y][content_x] == PORTAL)
+    }
+
+    /// Returns the line a walker going down the column of the line `y` continues on:
+    /// the next line, or the matching line of the target layer through a portal.
+    pub fn next_line_for_coords(&self, x: usize, y: usize) -> Result<usize, String> {
+        if !self.is_portal(x, y)? {
+            return Ok(y + 1);
+        }
+
+        let portal_error = || format!("Portal on line {} column {} leads nowhere", y + 1, x + 1);
+        let layer = self.layer_for_line(y).ok_or_else(portal_error)?;
+        let target = self.layers[layer]
+            .target
+            .as_ref()
+            .and_then(|target| self.layers.iter().position(|l| &l.name == target))
+            .ok_or_else(portal_error)?;
+        let line = self.layers[target].start + (y - self.layers[layer].start) + 1;
+
+        // Target layers shorter than the one left are walked out of right away
+        Ok(line.min(self.layer_end(target)))
+    }
+
+    /// Checks that layers are in order, that they lead to existing layers and that every portal has a target.
+    pub fn validate_layers(&self) -> Result<(), String> {
+        if self
+            .layers
+            .windows(2)
+            .any(|layers| layers[0].start > layers[1].start)
+            || self.layers.last().is_some_and(|l| l.start > self.height)
+        {
+            return Err("Layers are not in the order of the lines".to_owned());
+        }
+        for layer in &self.layers {
+            if let Some(target) = &layer.target {
+                if !self.layers.iter().any(|other| &other.name == target) {
+                    return Err(format!(
+                        "Layer {} leads to unknown layer {}",
+                        layer.name, target
+                    ));
+                }
+            }
+        }
+        for y in 0..self.height {
+            for x in 0..self.column_count(y)? {
+                if self.is_portal(x, y)? {
+                    self.next_line_for_coords(x, y)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails for maps with portals, which the provided command walks line after line.
+    pub fn check_no_portals(&self, command: &str) -> Result<(), String> {
+        if self.has_portals() {
+            Err(format!("{} does not support maps with portals", command))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,27 @@
+pub mod animation;
+pub mod batch;
+pub mod check;
+pub mod compact;
+pub mod cost;
+pub mod count;
+pub mod edit;
+pub mod error;
+pub mod fairness;
+pub mod fill;
+pub mod generator;
+pub mod json;
+pub mod layer;
+pub mod lint;
+pub mod map;
+pub mod multiplayer;
+pub mod schema;
+pub mod sensitivity;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod stream;
+pub mod suggestion;
+pub mod svg;
+pub mod view;
+pub mod walker;
+
+mod tests;
@@ -0,0 +1,274 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::fill::UNKNOWN;
+use crate::map::{Map, Outcome, OBSTACLE};
+
+/// Kinds of suspicious (but legal) layouts the linter looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningCode {
+    /// A line without any column.
+    EmptyLine,
+    /// Characters that are neither columns nor rungs, ignored when solving.
+    Garbage,
+    /// A rung leaving the first or last column that does not lead anywhere.
+    DanglingRung,
+    /// The same rung on two consecutive lines, the second one cancelling the first one.
+    CancellingRungs,
+    /// An entry ending up in its own column.
+    SelfExit,
+    /// A line with a different number of columns than the number of entries.
+    ColumnCount,
+    /// An entry that never reaches an exit, stopped by an obstacle.
+    Trapped,
+    /// A rung on the last line that no walker reaching an exit crosses: removing it changes no exit.
+    UselessLastRung,
+}
+
+impl WarningCode {
+    pub const ALL: [WarningCode; 8] = [
+        WarningCode::EmptyLine,
+        WarningCode::Garbage,
+        WarningCode::DanglingRung,
+        WarningCode::CancellingRungs,
+        WarningCode::SelfExit,
+        WarningCode::ColumnCount,
+        WarningCode::Trapped,
+        WarningCode::UselessLastRung,
+    ];
+
+    /// Returns the code used to display and suppress the warning.
+    pub fn code(&self) -> &'static str {
+        match self {
+            WarningCode::EmptyLine => "W001",
+            WarningCode::Garbage => "W002",
+            WarningCode::DanglingRung => "W003",
+            WarningCode::CancellingRungs => "W004",
+            WarningCode::SelfExit => "W005",
+            WarningCode::ColumnCount => "W006",
+            WarningCode::Trapped => "W007",
+            WarningCode::UselessLastRung => "W008",
+        }
+    }
+}
+
+impl FromStr for WarningCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<WarningCode, String> {
+        WarningCode::ALL
+            .iter()
+            .find(|code| code.code() == s)
+            .copied()
+            .ok_or(format!("Unknown warning code {}", s))
+    }
+}
+
+/// A suspicious layout found in a map. Line and column are 1-based map coordinates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub code: WarningCode,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code.code())?;
+        if let Some(line) = self.line {
+            write!(f, " line {}", line)?;
+        }
+        if let Some(column) = self.column {
+            write!(f, " column {}", column)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Looks for suspicious layouts in the map, skipping the `allowed` warning codes.
+pub fn lint(map: &Map, allowed: &[WarningCode]) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (y, line) in map.content.iter().enumerate() {
+        lint_line(map, y, line, &mut warnings);
+    }
+    lint_cancelling_rungs(map, &mut warnings);
+    lint_self_exits(map, &mut warnings);
+    lint_trapped_entries(map, &mut warnings);
+    lint_useless_last_rungs(map, &mut warnings);
+    warnings.retain(|warning| !allowed.contains(&warning.code));
+    warnings
+}
+
+/// Checks a single content line for empty lines, garbage, dangling rungs and column count.
+fn lint_line(map: &Map, y: usize, line: &[char], warnings: &mut Vec<Warning>) {
+    let columns = line.iter().filter(|c| Map::is_pole(c)).count();
+    // Weights written inside rungs are the only characters in between columns that are not ignored
+    let garbage = match columns {
+        0 => line.to_vec(),
+        _ => (0..columns)
+            .filter_map(|x| map.rung_segment(x, y).ok())
+            .filter(|segment| Map::weight_in_segment(segment).is_none())
+            .flatten()
+            .collect(),
+    }
+    .into_iter()
+    .filter(|c| c != &'-' && c != &UNKNOWN)
+    .collect::<String>();
+
+    if columns == 0 {
+        warnings.push(Warning {
+            code: WarningCode::EmptyLine,
+            line: Some(y + 1),
+            column: None,
+            message: "Line has no column".to_owned(),
+        });
+    } else if columns != map.entries.len() {
+        warnings.push(Warning {
+            code: WarningCode::ColumnCount,
+            line: Some(y + 1),
+            column: None,
+            message: format!(
+                "Line has {} columns but the map has {} entries",
+                columns,
+                map.entries.len()
+            ),
+        });
+    }
+    if !garbage.is_empty() {
+        warnings.push(Warning {
+            code: WarningCode::Garbage,
+            line: Some(y + 1),
+            column: None,
+            message: format!("Characters \"{}\" are ignored", garbage),
+        });
+    }
+    if columns > 0 && !map.has_wrap_rung(y).unwrap_or(false) {
+        let first = line.iter().position(Map::is_pole).unwrap_or(0);
+        let last = line.iter().rposition(Map::is_pole).unwrap_or(0);
+
+        if line[..first].contains(&'-') {
+            warnings.push(Warning {
+                code: WarningCode::DanglingRung,
+                line: Some(y + 1),
+                column: Some(1),
+                message: "Rung on the left of the first column leads nowhere".to_owned(),
+            });
+        }
+        if line[last + 1..].contains(&'-') {
+            warnings.push(Warning {
+                code: WarningCode::DanglingRung,
+                line: Some(y + 1),
+                column: Some(columns),
+                message: "Rung on the right of the last column leads nowhere".to_owned(),
+            });
+        }
+    }
+}
+
+/// Checks for rungs directly repeated on the next line, which swap the paths back.
+fn lint_cancelling_rungs(map: &Map, warnings: &mut Vec<Warning>) {
+    for y in 1..map.content.len() {
+        let columns = map
+            .column_count(y - 1)
+            .unwrap_or(0)
+            .min(map.column_count(y).unwrap_or(0));
+
+        for x in 0..columns {
+            let above = map.next_column_for_coords(x, y - 1);
+            let below = map.next_column_for_coords(x, y);
+
+            // Each rung is seen from both of its columns, only report it from the leftmost one
+            if let (Ok(above), Ok(below)) = (above, below) {
+                if above == below && above > x {
+                    warnings.push(Warning {
+                        code: WarningCode::CancellingRungs,
+                        line: Some(y + 1),
+                        column: Some(x + 1),
+                        message: format!("Rung cancels the same rung on line {}", y),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Checks for entries that end up right below themselves.
+fn lint_self_exits(map: &Map, warnings: &mut Vec<Warning>) {
+    for (x, entry) in map.entries.iter().enumerate() {
+        if let (Ok(trace), Ok(Outcome::Exit(_))) =
+            (map.trace_for_entry(entry), map.outcome_for_entry(entry))
+        {
+            if trace[trace.len() - 1] == x {
+                warnings.push(Warning {
+                    code: WarningCode::SelfExit,
+                    line: None,
+                    column: Some(x + 1),
+                    message: format!("Entry {} ends up in its own column", entry),
+                });
+            }
+        }
+    }
+}
+
+/// Checks for entries stopped by an obstacle before reaching any exit.
+fn lint_trapped_entries(map: &Map, warnings: &mut Vec<Warning>) {
+    for entry in &map.entries {
+        if let Ok(Outcome::Trapped { line, column }) = map.outcome_for_entry(entry) {
+            warnings.push(Warning {
+                code: WarningCode::Trapped,
+                line: Some(line + 1),
+                column: Some(column + 1),
+                message: format!(
+                    "Entry {} is stopped by an obstacle '{}' and never reaches an exit",
+                    entry, OBSTACLE
+                ),
+            });
+        }
+    }
+}
+
+/// Checks for rungs of the last line that only walkers trapped by obstacles cross (or no walker at all).
+/// Every other rung changes the exits of the walkers crossing it, the last line being the last chance to do so.
+fn lint_useless_last_rungs(map: &Map, warnings: &mut Vec<Warning>) {
+    // Maps that can not be walked are left to the other checks
+    let walks = map
+        .entries
+        .iter()
+        .map(|entry| map.walk_for_entry(entry))
+        .collect::<Result<Vec<_>, String>>();
+    let (last, walks) = match (map.height.checked_sub(1), walks) {
+        (Some(last), Ok(walks)) => (last, walks),
+        _ => return,
+    };
+    let mut crossed = vec![];
+
+    for (x, (steps, outcome)) in walks.into_iter().enumerate() {
+        if let Outcome::Exit(_) = outcome {
+            let mut from = x;
+
+            for (y, to) in steps {
+                if y == last {
+                    if let Ok(Some(rung)) = map.crossed_rung(y, from, to) {
+                        crossed.push(rung);
+                    }
+                }
+                from = to;
+            }
+        }
+    }
+    if let Ok(rungs) = map.rungs_for_line(last) {
+        for (x, _) in rungs.iter().enumerate().filter(|(_, rung)| **rung) {
+            if !crossed.contains(&x) {
+                warnings.push(Warning {
+                    code: WarningCode::UselessLastRung,
+                    line: Some(last + 1),
+                    column: Some(x + 1),
+                    message: "Rung on the last line is crossed by no walker reaching an exit"
+                        .to_owned(),
+                });
+            }
+        }
+    }
+}
@@ -1,62 +1,458 @@
 use std::io;
+use std::path::Path;
 
-mod map;
-mod tests;
+use stick_path::error::{CliError, ErrorFormat};
+use stick_path::generator::Rng;
+use stick_path::lint::{self, WarningCode};
+use stick_path::map::{Map, Outcome};
+use stick_path::multiplayer::{self, CollisionPolicy};
+use stick_path::stream::MapReader;
+use stick_path::walker::{self, Walker};
+use stick_path::{batch, check, cost, count, fairness, fill, sensitivity, suggestion, view};
 
-use map::Map;
+/// Reads the first map of the standard input, without validating it.
+/// Returns an `Err` if the input is incorrect.
+fn read_map(circular: bool) -> Result<Map, CliError> {
+    let mut map = MapReader::new(io::stdin().lock())
+        .next()
+        .ok_or(CliError::parse("Missing map size"))??;
 
-macro_rules! parse_input {
-    ($x:expr, $t:ident) => {
-        $x.trim().parse::<$t>().unwrap()
-    };
+    if map.inferred_size {
+        eprintln!("Inferred map size: {} {}", map.width, map.text_height());
+    }
+    map.circular = circular;
+    Ok(map)
 }
 
 /// Reads the standards input to construct a `Map` struct.
 /// Returns an `Err` if the input is incorrect or the map could not be validated.
-fn get_map() -> Result<Map, String> {
-    // Reads standard input (first line defines the number of lines read)
-    let mut input_line = String::new();
+fn get_map(circular: bool) -> Result<Map, CliError> {
+    let map = read_map(circular)?;
 
-    io::stdin()
-        .read_line(&mut input_line)
-        .expect("Could not get map info");
+    map.validate().map_err(CliError::validation)?;
+    Ok(map)
+}
+
+/// Prints the exit of every entry of the map, or where it got trapped.
+fn solve(map: &Map) -> Result<(), String> {
+    for (entry, outcome) in map.outcomes()? {
+        match outcome {
+            Outcome::Exit(exit) => println!("{}{}", entry, exit),
+            trapped => println!("{} {}", entry, trapped),
+        }
+    }
+    Ok(())
+}
 
-    let inputs: Vec<&str> = input_line.split(' ').collect::<Vec<_>>();
-    let width: usize = parse_input!(inputs[0], usize);
-    let height: usize = parse_input!(inputs[1], usize);
-    let mut content: Vec<String> = vec![];
+/// Prints the exit and the path cost of every entry of the map, then the cheapest and most expensive ones.
+fn solve_with_costs(map: &Map) -> Result<(), String> {
+    let costs = cost::costs(map)?;
 
-    for _ in 0..height {
-        let mut input_line = String::new();
+    for cost in &costs {
+        match &cost.outcome {
+            Outcome::Exit(exit) => println!("{}{} {}", cost.entry, exit, cost.cost),
+            trapped => println!("{} {} {}", cost.entry, trapped, cost.cost),
+        }
+    }
+    if let Some(summary) = cost::summary(&costs) {
+        print!("\n{}", summary);
+    }
+    Ok(())
+}
 
-        io::stdin()
-            .read_line(&mut input_line)
-            .expect("Could not read line from standard input");
+/// Solves every map of the standard input in turn, the output of each map being separated by a blank line.
+/// Errors are reported with the index of their map, the next maps are still solved.
+/// Returns the exit code of the first map that could not be solved, 0 if all of them were.
+fn solve_all(circular: bool, costs: bool, format: ErrorFormat) -> Result<i32, CliError> {
+    let mut count = 0;
+    let mut code = 0;
 
-        content.push(input_line.trim_end().to_owned());
+    for (i, map) in MapReader::new(io::stdin().lock()).enumerate() {
+        // A map that can not be solved keeps its (empty) group so that groups match the maps
+        if i > 0 {
+            println!();
+        }
+
+        let result = map.map_err(CliError::from).and_then(|mut map| {
+            if map.inferred_size {
+                eprintln!(
+                    "Map {}: inferred size {} {}",
+                    i + 1,
+                    map.width,
+                    map.text_height()
+                );
+            }
+            map.circular = circular;
+            map.validate().map_err(CliError::validation)?;
+            if costs {
+                solve_with_costs(&map)
+            } else {
+                solve(&map)
+            }
+            .map_err(CliError::solve)
+        });
+
+        if let Err(err) = result {
+            let err = err.for_map(i + 1);
+
+            eprintln!("{}", err.render(format));
+            if code == 0 {
+                code = err.kind.exit_code();
+            }
+        }
+        count += 1;
+    }
+    if count == 0 {
+        Err(CliError::parse("Missing map size"))
+    } else {
+        Ok(code)
     }
+}
 
-    // Construct the map struct and validates it
-    let map = Map::from_size_and_content(width, height, content)?;
+/// Prints the label each walker finishes on, every entry going down then every exit going up by default.
+fn walk(map: &Map, specs: &[&str]) -> Result<(), CliError> {
+    let walkers = if specs.is_empty() {
+        Walker::both_ways(map)
+    } else {
+        specs
+            .iter()
+            .map(|spec| Walker::for_label(map, spec).map_err(CliError::usage))
+            .collect::<Result<Vec<_>, _>>()?
+    };
 
-    map.validate()?;
-    Ok(map)
+    for result in walker::simulate(map, &walkers).map_err(CliError::solve)? {
+        println!("{}", result);
+    }
+    Ok(())
 }
 
-fn main() {
-    match get_map() {
-        Ok(map) => {
-            for entry in &map.entries {
-                println!(
-                    "{}{}",
-                    entry,
-                    map.exit_for_entry(entry)
-                        .expect("Could not find exit for entry")
-                );
+/// Walks every entry down at the same time and prints where each one finished and the tick it got there.
+fn race(map: &Map, policy: CollisionPolicy) -> Result<(), CliError> {
+    for arrival in multiplayer::play(map, policy).map_err(CliError::solve)? {
+        println!("{}", arrival);
+    }
+    Ok(())
+}
+
+/// Animates the walk of the provided entry (or the first one) in the terminal.
+fn view(map: &Map, entry: Option<&str>) -> Result<(), CliError> {
+    let entry = entry
+        .or_else(|| map.entries.first().map(|e| e.as_str()))
+        .ok_or(CliError::solve("Map has no entry to view"))?;
+
+    view::view(map, entry)
+}
+
+/// Prints the warnings found in the map, then fails with its validation error if any.
+fn lint(map: &Map, allowed: &[WarningCode]) -> Result<(), CliError> {
+    for warning in lint::lint(map, allowed) {
+        println!("{}", warning);
+    }
+    map.validate().map_err(CliError::validation)
+}
+
+/// Prints the smallest set of changes making the entry reach the exit, with the traces before and after.
+fn suggest(map: &Map, entry: Option<&str>, exit: Option<&str>) -> Result<(), CliError> {
+    let (entry, exit) = entry
+        .zip(exit)
+        .ok_or(CliError::usage("Usage: suggest ENTRY EXIT"))?;
+    let suggestion = suggestion::suggest(map, entry, exit).map_err(CliError::solve)?;
+
+    for change in &suggestion.changes {
+        println!("{}", change);
+    }
+    println!("\nBefore:\n{}", map.render_trace(&suggestion.before));
+    println!(
+        "\nAfter:\n{}",
+        suggestion.map.render_trace(&suggestion.after)
+    );
+    print!("\n{}", suggestion.map);
+    Ok(())
+}
+
+/// Prints the rungs on the path of the entry, with the entries sharing them.
+fn critical(map: &Map, entry: Option<&str>) -> Result<(), CliError> {
+    let entry = entry.ok_or(CliError::usage("Usage: critical ENTRY"))?;
+
+    for rung in map.critical_rungs(entry).map_err(CliError::solve)? {
+        println!("{}", rung);
+    }
+    Ok(())
+}
+
+/// Prints, for each rung of the map, the entries that would change exit without it.
+fn sensitivity(map: &Map) -> Result<(), CliError> {
+    for rung in sensitivity::sensitivity(map).map_err(CliError::solve)? {
+        println!("{}", rung);
+    }
+    Ok(())
+}
+
+/// Parses the optional height of the ladders to count, defaulting to the height of the map.
+fn ladder_height(map: &Map, height: Option<&str>) -> Result<usize, CliError> {
+    height.map_or(Ok(map.height), |height| {
+        height
+            .parse::<usize>()
+            .map_err(|e| CliError::usage(e.to_string()))
+    })
+}
+
+/// Prints how many ladders of the same width (and of the map height or the provided one) give the same exits as the map.
+fn count(mut map: Map, height: Option<&str>) -> Result<(), CliError> {
+    let height = ladder_height(&map, height)?;
+    let circular = map.circular;
+    let permutation = map.permutation().map_err(CliError::solve)?;
+
+    println!(
+        "{}",
+        count::count(permutation, height, circular).map_err(CliError::solve)?
+    );
+    Ok(())
+}
+
+/// Prints every ladder of the same width (and of the map height or the provided one) giving the same exits as the map.
+fn enumerate(mut map: Map, height: Option<&str>) -> Result<(), CliError> {
+    let height = ladder_height(&map, height)?;
+    let permutation = map.permutation().map_err(CliError::solve)?.to_vec();
+    let ladders = count::ladders(
+        map.entries.clone(),
+        map.exits.clone(),
+        &permutation,
+        height,
+        map.circular,
+    )
+    .map_err(CliError::solve)?;
+
+    for (i, ladder) in ladders.enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print!("{}", ladder);
+    }
+    Ok(())
+}
+
+/// Prints every way of filling the unknown rungs of the map so that the entries reach the exits of the expected file
+/// (in the `{entry}{exit}` format), reporting their number on the standard error. Fails if there is none.
+fn fill(map: &Map, expected: Option<&str>) -> Result<(), CliError> {
+    let path = expected.ok_or(CliError::usage("Usage: fill EXPECTED"))?;
+    let expected = std::fs::read_to_string(path)
+        .map_err(|e| CliError::io(format!("Could not read expected file {}: {}", path, e)))?;
+    let expected = check::parse_expected(&expected, map).map_err(CliError::parse)?;
+    let completions = fill::fill(map, &expected).map_err(CliError::solve)?;
+
+    if completions.is_empty() {
+        return Err(CliError::solve(
+            "No way of filling the unknown rungs gives the expected exits",
+        ));
+    }
+    for (i, completion) in completions.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print!("{}", completion);
+    }
+    eprintln!("Completions: {}", completions.len());
+    Ok(())
+}
+
+/// Prints the compacted map, reporting the old and new heights on the standard error.
+fn compact(map: &Map) -> Result<(), CliError> {
+    let compacted = map.compact().map_err(CliError::solve)?;
+
+    eprintln!("Height: {} -> {}", map.height, compacted.height);
+    print!("{}", compacted);
+    Ok(())
+}
+
+/// Prints the fairness of random ladders generated with the provided parameters.
+fn fairness(params: &[&str], circular: bool, seed: Option<u64>) -> Result<(), CliError> {
+    let usage = "Usage: fairness COLUMNS HEIGHT DENSITY [TRIALS]";
+    let number = |i: usize| -> Result<&str, CliError> {
+        params.get(i).copied().ok_or(CliError::usage(usage))
+    };
+    let invalid = |e: &dyn std::fmt::Display| CliError::usage(e.to_string());
+    let columns = number(1)?.parse::<usize>().map_err(|e| invalid(&e))?;
+    let height = number(2)?.parse::<usize>().map_err(|e| invalid(&e))?;
+    let density = number(3)?.parse::<f64>().map_err(|e| invalid(&e))?;
+    let trials = match params.get(4) {
+        Some(trials) => trials.parse::<usize>().map_err(|e| invalid(&e))?,
+        None => 10_000,
+    };
+    let mut rng = seed.map(Rng::new).unwrap_or_else(Rng::from_time);
+    let report = fairness::analyse(columns, height, density, circular, trials, &mut rng)
+        .map_err(CliError::solve)?;
+
+    print!("{}", report);
+    Ok(())
+}
+
+/// Solves the maps of a directory or a manifest in parallel, printing one JSON line per map.
+fn batch(source: Option<&str>, workers: Option<usize>, circular: bool) -> Result<(), CliError> {
+    let source = source.ok_or(CliError::usage(
+        "Usage: batch DIRECTORY|MANIFEST [--workers=N]",
+    ))?;
+    let paths = batch::map_paths(Path::new(source)).map_err(CliError::io)?;
+    let workers = workers.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    });
+
+    batch::run(paths, workers, circular, |result| {
+        println!("{}", result.to_json())
+    });
+    Ok(())
+}
+
+/// Compares the exits of each map with its `.expected` file. Returns `false` on any mismatch.
+fn check(sources: &[&str], circular: bool) -> Result<bool, CliError> {
+    if sources.is_empty() {
+        return Err(CliError::usage("Usage: check MAP|DIRECTORY..."));
+    }
+
+    let mut success = true;
+
+    for source in sources {
+        let source = Path::new(source);
+        let paths = if source.is_dir() {
+            batch::map_paths(source).map_err(CliError::io)?
+        } else {
+            vec![source.to_owned()]
+        };
+
+        for path in paths {
+            match check::check_file(&path, circular) {
+                Ok(mismatches) if mismatches.is_empty() => println!("{}: ok", path.display()),
+                Ok(mismatches) => {
+                    success = false;
+                    for mismatch in mismatches {
+                        println!("{}: {}", path.display(), mismatch);
+                    }
+                }
+                Err(err) => {
+                    success = false;
+                    println!("{}: {}", path.display(), err);
+                }
             }
         }
-        Err(err) => {
-            eprintln!("{}", err)
+    }
+    Ok(success)
+}
+
+/// Parses the number of workers provided with `--workers=N`.
+fn workers(args: &[String]) -> Result<Option<usize>, CliError> {
+    args.iter()
+        .find_map(|arg| arg.strip_prefix("--workers="))
+        .map(|workers| {
+            workers
+                .parse::<usize>()
+                .map_err(|e| CliError::usage(e.to_string()))
+        })
+        .transpose()
+}
+
+/// Parses the collision policy provided with `--collisions=wait|bounce|swap`.
+fn collisions(args: &[String]) -> Result<CollisionPolicy, CliError> {
+    args.iter()
+        .find_map(|arg| arg.strip_prefix("--collisions="))
+        .map_or(Ok(CollisionPolicy::default()), |policy| {
+            policy.parse().map_err(CliError::usage)
+        })
+}
+
+/// Parses the seed provided with `--seed=N`.
+fn seed(args: &[String]) -> Result<Option<u64>, CliError> {
+    args.iter()
+        .find_map(|arg| arg.strip_prefix("--seed="))
+        .map(|seed| {
+            seed.parse::<u64>()
+                .map_err(|e| CliError::usage(e.to_string()))
+        })
+        .transpose()
+}
+
+/// Parses the warning codes provided with `--allow=W001,W002`.
+fn allowed_warnings(args: &[String]) -> Result<Vec<WarningCode>, CliError> {
+    args.iter()
+        .filter_map(|arg| arg.strip_prefix("--allow="))
+        .flat_map(|codes| codes.split(','))
+        .map(|code| code.parse().map_err(CliError::usage))
+        .collect()
+}
+
+/// Parses the format of the errors provided with `--error-format json` (or `--error-format=json`).
+/// Returns the format, and the index of its value in the arguments when it is a separate one.
+fn error_format(args: &[String]) -> (Result<ErrorFormat, CliError>, Option<usize>) {
+    for (i, arg) in args.iter().enumerate() {
+        if let Some(format) = arg.strip_prefix("--error-format=") {
+            return (format.parse().map_err(CliError::usage), None);
+        }
+        if arg == "--error-format" {
+            return match args.get(i + 1) {
+                Some(format) => (format.parse().map_err(CliError::usage), Some(i + 1)),
+                None => (
+                    Err(CliError::usage("Usage: --error-format text|json")),
+                    None,
+                ),
+            };
         }
     }
+    (Ok(ErrorFormat::Text), None)
+}
+
+/// Runs the command of the arguments. Returns the code the program exits with.
+fn run(args: &[String], params: &[&str], format: ErrorFormat) -> Result<i32, CliError> {
+    let circular = args.iter().any(|arg| arg == "--circular");
+    let costs = args.iter().any(|arg| arg == "--costs");
+
+    match params.first().copied() {
+        None => return solve_all(circular, costs, format),
+        Some("walk") => walk(&get_map(circular)?, &params[1..])?,
+        Some("race") => race(&get_map(circular)?, collisions(args)?)?,
+        Some("view") => view(&get_map(circular)?, params.get(1).copied())?,
+        Some("suggest") => suggest(
+            &get_map(circular)?,
+            params.get(1).copied(),
+            params.get(2).copied(),
+        )?,
+        Some("critical") => critical(&get_map(circular)?, params.get(1).copied())?,
+        Some("sensitivity") => sensitivity(&get_map(circular)?)?,
+        Some("count") => count(get_map(circular)?, params.get(1).copied())?,
+        Some("enumerate") => enumerate(get_map(circular)?, params.get(1).copied())?,
+        Some("fill") => fill(&read_map(circular)?, params.get(1).copied())?,
+        Some("compact") => compact(&get_map(circular)?)?,
+        Some("fairness") => fairness(params, circular, seed(args)?)?,
+        Some("batch") => batch(params.get(1).copied(), workers(args)?, circular)?,
+        // Mismatches are not errors of the command, they get their own code
+        Some("check") => return Ok(if check(&params[1..], circular)? { 0 } else { 1 }),
+        Some("lint") => {
+            let allowed = allowed_warnings(args)?;
+
+            lint(&read_map(circular)?, &allowed)?
+        }
+        Some(command) => return Err(CliError::usage(format!("Unknown command {}", command))),
+    }
+    Ok(0)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (format, format_value) = error_format(&args);
+    let params: Vec<&str> = args
+        .iter()
+        .enumerate()
+        .filter(|(i, arg)| !arg.starts_with("--") && Some(*i) != format_value)
+        .map(|(_, arg)| arg.as_str())
+        .collect();
+    let format_or_text = *format.as_ref().unwrap_or(&ErrorFormat::Text);
+    let code = match format.and_then(|format| run(&args, &params, format)) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{}", err.render(format_or_text));
+            err.kind.exit_code()
+        }
+    };
+
+    std::process::exit(code);
 }
@@ -1,10 +1,93 @@
-#[derive(Debug)]
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::edit::History;
+use crate::fill::UNKNOWN;
+use crate::layer::{Layer, PORTAL};
+
+/// Character replacing a pole on a line where it is broken: walkers can not go down it.
+pub const OBSTACLE: char = 'x';
+
+/// Way a walker goes through the map: down from an entry or up from an exit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Down,
+    Up,
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Direction, String> {
+        match s {
+            "down" => Ok(Direction::Down),
+            "up" => Ok(Direction::Up),
+            _ => Err(format!("Unknown direction {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Down => write!(f, "down"),
+            Direction::Up => write!(f, "up"),
+        }
+    }
+}
+
+/// Where the walk of an entry ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Outcome {
+    /// Label the walker finishes on: an exit, or an entry for walkers going up.
+    Exit(String),
+    /// The walker has to go down a broken pole, line and column being the ones of the obstacle.
+    Trapped { line: usize, column: usize },
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Exit(exit) => write!(f, "{}", exit),
+            Outcome::Trapped { line, column } => {
+                write!(f, "trapped on line {} column {}", line + 1, column + 1)
+            }
+        }
+    }
+}
+
+/// Serialized through its versioned schema (cf. schema::MapSchema) with the `serde` feature.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "crate::schema::MapSchema",
+        try_from = "crate::schema::MapSchema"
+    )
+)]
 pub struct Map {
     pub width: usize,
     pub height: usize,
     pub entries: Vec<String>,
     pub exits: Vec<String>,
     pub content: Vec<Vec<char>>,
+    /// When set, a rung marked on both edges of a row (`-| | |-`) links the last column back to the first one.
+    pub circular: bool,
+    /// Set when the map was read without a size line, its size being worked out from its lines.
+    pub inferred_size: bool,
+    /// Named parts of the map, in the order of their lines (cf. layer module). Empty for maps without headers.
+    pub layers: Vec<Layer>,
+    /// Edits that can be undone and redone (cf. edit module).
+    pub(crate) history: History,
+    /// Exit column of each entry column, kept up to date by the edits once computed.
+    pub(crate) permutation: Option<Vec<usize>>,
 }
 
 impl Map {
@@ -23,25 +106,187 @@ impl Map {
         height: usize,
         content: Vec<String>,
     ) -> Result<Map, String> {
-        if content.len() >= height {
+        if height < 2 {
+            Err("Map needs at least an entries and an exits line".to_owned())
+        } else if content.len() >= height {
             let entries = Map::string_into_clean_vec(&content[0]);
             let exits = Map::string_into_clean_vec(&content[height - 1]);
+            let (content, layers) = Map::split_layers(&content[1..=(height - 2)])?;
 
             Ok(Map {
                 width,
-                height: height - 2,
+                height: content.len(),
                 entries,
                 exits,
-                content: content[1..=(height - 2)]
-                    .iter()
-                    .map(|l| l.split(' ').collect::<String>().chars().collect())
-                    .collect::<Vec<_>>(),
+                content,
+                circular: false,
+                inferred_size: false,
+                layers,
+                history: History::default(),
+                permutation: None,
             })
         } else {
             Err("Content with not enough lines".to_owned())
         }
     }
 
+    /// Creates a `Map` struct from lines without size line (entries and exits included):
+    /// the height is the number of lines and the width the length of the longest one.
+    pub fn from_lines(lines: Vec<String>) -> Result<Map, String> {
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut map = Map::from_size_and_content(width, lines.len(), lines)?;
+
+        map.inferred_size = true;
+        Ok(map)
+    }
+
+    /// Tells you if the first line of a map is a size line, `next` being the line after it.
+    /// Labels can be numbers too, so the line must hold a positive width and height only and be followed
+    /// by an entries line: a line with poles makes it the entries line itself, as in `1 2` then `| |`.
+    pub fn is_header(line: &str, next: Option<&str>) -> bool {
+        Map::size_from_header(line).is_ok_and(|(width, height)| width > 0 && height > 0)
+            && next.is_none_or(|next| !next.contains('|'))
+    }
+
+    /// Parses the first line of a map, holding its width and its height.
+    pub fn size_from_header(header: &str) -> Result<(usize, usize), String> {
+        let size = header
+            .split_whitespace()
+            .map(|n| {
+                n.parse::<usize>()
+                    .map_err(|e| format!("Invalid map size: {}", e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match size[..] {
+            [width, height] => Ok((width, height)),
+            _ => Err("Map size must be a width and a height".to_owned()),
+        }
+    }
+
+    /// Creates a `Map` struct from the size read on its size line and its content, checking that the width
+    /// holds the entries and the exits, one space in between labels. Rungs are drawn with any count of
+    /// dashes and lines can be padded, so the width is not checked against the other lines.
+    pub fn from_header_and_content(
+        width: usize,
+        height: usize,
+        content: Vec<String>,
+    ) -> Result<Map, String> {
+        let map = Map::from_size_and_content(width, height, content)?;
+        let needed = [&map.entries, &map.exits]
+            .iter()
+            .map(|labels| labels.join(" ").chars().count())
+            .max()
+            .unwrap_or(0);
+
+        if width < needed {
+            return Err(format!(
+                "Map width {} does not match its labels, which need {} characters",
+                width, needed
+            ));
+        }
+        Ok(map)
+    }
+
+    /// Creates a `Map` struct from its labels and, for each line, its rungs (cf. rungs_for_line).
+    pub fn from_rungs(
+        entries: Vec<String>,
+        exits: Vec<String>,
+        rungs: &[Vec<bool>],
+        circular: bool,
+    ) -> Map {
+        let content = rungs
+            .iter()
+            .map(|line| Map::line_for_rungs(line, circular))
+            .collect::<Vec<_>>();
+        let mut map = Map {
+            width: 0,
+            height: content.len(),
+            entries,
+            exits,
+            content,
+            circular,
+            inferred_size: false,
+            layers: vec![],
+            history: History::default(),
+            permutation: None,
+        };
+
+        map.width = map.rendered_width();
+        map
+    }
+
+    /// Returns the width of the map once rendered (cf. Display): the length of its longest line, labels included.
+    pub fn rendered_width(&self) -> usize {
+        self.content
+            .iter()
+            .map(|line| Map::render_line(line).chars().count())
+            .chain([
+                self.entries.join(" ").chars().count(),
+                self.exits.join(" ").chars().count(),
+            ])
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the map walked from the bottom: following an exit leads to the entry reaching it.
+    pub fn inverse(&self) -> Map {
+        Map {
+            entries: self.exits.clone(),
+            exits: self.entries.clone(),
+            ..self.mirror()
+        }
+    }
+
+    /// Returns the map upside down, keeping its entries and exits. Layers are dropped.
+    pub fn mirror(&self) -> Map {
+        Map {
+            width: self.width,
+            height: self.height,
+            entries: self.entries.clone(),
+            exits: self.exits.clone(),
+            content: self.content.iter().rev().cloned().collect(),
+            circular: self.circular,
+            inferred_size: false,
+            layers: vec![],
+            history: History::default(),
+            permutation: None,
+        }
+    }
+
+    /// Returns the map followed by the provided one: the walk continues in the same column at the top of `other`.
+    pub fn stack(&self, other: &Map) -> Map {
+        Map {
+            width: self.width.max(other.width),
+            height: self.height + other.height,
+            entries: self.entries.clone(),
+            exits: other.exits.clone(),
+            content: self
+                .content
+                .iter()
+                .chain(other.content.iter())
+                .cloned()
+                .collect(),
+            circular: self.circular,
+            inferred_size: false,
+            layers: self
+                .layers
+                .iter()
+                .cloned()
+                .chain(other.layers.iter().map(|layer| Layer {
+                    start: layer.start + self.height,
+                    ..layer.clone()
+                }))
+                .collect(),
+            history: History::default(),
+            permutation: None,
+        }
+    }
+
     /// Validates the correctness of the map.
     pub fn validate(&self) -> Result<(), String> {
         let has_same_entries_exits_count = self.entries.len() == self.exits.len();
@@ -52,7 +297,90 @@ impl Map {
         } else if !has_correct_height {
             Err("Map does not have the correct height".to_owned())
         } else {
-            Ok(())
+            self.validate_rungs()?;
+            self.validate_layers()
+        }
+    }
+
+    /// Checks that every line has a pole per entry, that no rung is unknown, that no column is linked on both sides on
+    /// the same line and that wrap rungs are complete.
+    fn validate_rungs(&self) -> Result<(), String> {
+        for (y, line) in self.content.iter().enumerate() {
+            let columns = self.column_count(y)?;
+            if columns != self.entries.len() {
+                return Err(format!(
+                    "Line {} has {} poles for {} entries",
+                    y + 1,
+                    columns,
+                    self.entries.len()
+                ));
+            }
+            if line.contains(&UNKNOWN) {
+                return Err(format!("Line {} has unknown rungs left to fill", y + 1));
+            }
+            if self.circular && Map::has_left_edge_rung(line) != Map::has_right_edge_rung(line) {
+                return Err(format!(
+                    "Line {} has a wrap rung marked on only one edge",
+                    y + 1
+                ));
+            }
+            for x in 0..columns {
+                if let (true, true) = self.can_go_to_sides_for_coords(x, y)? {
+                    return Err(format!(
+                        "Line {} has conflicting rungs on both sides of column {}",
+                        y + 1,
+                        x + 1
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Tells you if the character is a column, broken, portal or not.
+    pub fn is_pole(c: &char) -> bool {
+        c == &'|' || c == &OBSTACLE || c == &PORTAL
+    }
+
+    /// Tells you if the column is broken on the provided line (cf. OBSTACLE).
+    pub fn is_blocked(&self, x: usize, y: usize) -> Result<bool, String> {
+        let (content_x, content_y) = self.content_coords_for_coords(x, y)?;
+
+        Ok(self.content[content_y][content_x] == OBSTACLE)
+    }
+
+    /// Returns the number of columns (poles) found on the provided line.
+    pub fn column_count(&self, y: usize) -> Result<usize, String> {
+        if let Some(line) = self.content.get(y) {
+            Ok(line.iter().filter(|c| Map::is_pole(c)).count())
+        } else {
+            Err("Map has not enough lines for the required coordinates".to_owned())
+        }
+    }
+
+    /// Tells you if the line starts with a rung leaving its first column to the left.
+    fn has_left_edge_rung(line: &[char]) -> bool {
+        match line.iter().position(Map::is_pole) {
+            Some(i) => i > 0 && line[i - 1] == '-',
+            None => false,
+        }
+    }
+
+    /// Tells you if the line ends with a rung leaving its last column to the right.
+    fn has_right_edge_rung(line: &[char]) -> bool {
+        match line.iter().rposition(Map::is_pole) {
+            Some(i) => i + 1 < line.len() && line[i + 1] == '-',
+            None => false,
+        }
+    }
+
+    /// Tells you if the line holds a rung wrapping from its last column back to its first one.
+    /// Always false if the map is not circular.
+    pub fn has_wrap_rung(&self, y: usize) -> Result<bool, String> {
+        if let Some(line) = self.content.get(y) {
+            Ok(self.circular && Map::has_left_edge_rung(line) && Map::has_right_edge_rung(line))
+        } else {
+            Err("Map has not enough lines for the required coordinates".to_owned())
         }
     }
 
@@ -63,7 +391,7 @@ impl Map {
             let mut col_count = 0;
 
             for (i, c) in line.iter().enumerate() {
-                if c == &'|' {
+                if Map::is_pole(c) {
                     if col_count == x {
                         return Ok((i, y));
                     }
@@ -82,7 +410,7 @@ impl Map {
         if let Some(line) = self.content.get(y) {
             // If x is 0 then there is no character on the left
             // If left char is a '-', then you can go left
-            if x > 0 && line[x - 1] == '-' {
+            if x > 0 && line.get(x - 1) == Some(&'-') {
                 Ok(true)
             } else {
                 Ok(false)
@@ -98,7 +426,7 @@ impl Map {
         if let Some(line) = self.content.get(y) {
             // If x is equal to line lenght - 1 then there is no character on the right
             // If right char is a '-', then you can go left
-            if x < line.len() - 1 && line[x + 1] == '-' {
+            if x + 1 < line.len() && line[x + 1] == '-' {
                 Ok(true)
             } else {
                 Ok(false)
@@ -109,15 +437,38 @@ impl Map {
     }
 
     /// Tells you if you can go to left for the provided coordinates. Coordinates MUST be normal coords (col/line).
+    /// Rungs leaving the first or last column are only followed when they form a wrap rung on a circular map.
     pub fn can_go_to_sides_for_coords(&self, x: usize, y: usize) -> Result<(bool, bool), String> {
         let content_coords = self.content_coords_for_coords(x, y)?;
+        let last_column = self.column_count(y)? - 1;
 
         Ok((
-            self.can_go_left_for_content_coords(content_coords.0, content_coords.1)?,
-            self.can_go_right_for_content_coords(content_coords.0, content_coords.1)?,
+            if x == 0 {
+                self.has_wrap_rung(y)?
+            } else {
+                self.can_go_left_for_content_coords(content_coords.0, content_coords.1)?
+            },
+            if x == last_column {
+                self.has_wrap_rung(y)?
+            } else {
+                self.can_go_right_for_content_coords(content_coords.0, content_coords.1)?
+            },
         ))
     }
 
+    /// Returns the column reached at the bottom of the line `y` when arriving on column `x`.
+    pub fn next_column_for_coords(&self, x: usize, y: usize) -> Result<usize, String> {
+        let last_column = self.column_count(y)?.saturating_sub(1);
+
+        Ok(match self.can_go_to_sides_for_coords(x, y)? {
+            (true, _) if x == 0 => last_column,
+            (true, _) => x - 1,
+            (_, true) if x == last_column => 0,
+            (_, true) => x + 1,
+            _ => x,
+        })
+    }
+
     /// Searches for the right columns to starting path from the provided entry.
     pub fn starting_coords_for_entry(&self, entry: &str) -> Result<(usize, usize), String> {
         for (i, entry_self) in self.entries.iter().enumerate() {
@@ -138,18 +489,344 @@ impl Map {
         Err("Exit not found in map".to_owned())
     }
 
+    /// Walks the provided entry down to its exit or to the obstacle trapping it, following portals.
+    /// Returns each line walked with the column reached at its bottom, and where the walk ends.
+    pub fn walk_for_entry(&self, entry: &str) -> Result<(Vec<(usize, usize)>, Outcome), String> {
+        self.walk(entry, Direction::Down)
+    }
+
+    /// Walks from the provided label in the provided direction: from an entry down to an exit, or from an exit
+    /// up to an entry. Returns each line walked with the column reached on it, and where the walk ends.
+    /// Going down, the pole below a line is walked after its rung, going up before it: a walker going up
+    /// is trapped by an obstacle below the line. Maps with portals can only be walked down.
+    pub fn walk(
+        &self,
+        label: &str,
+        direction: Direction,
+    ) -> Result<(Vec<(usize, usize)>, Outcome), String> {
+        let (starts, finishes) = match direction {
+            Direction::Down => (&self.entries, &self.exits),
+            Direction::Up => {
+                self.check_no_portals("Walking up")?;
+                (&self.exits, &self.entries)
+            }
+        };
+        let mut x = match starts.iter().position(|start| start == label) {
+            Some(x) => x,
+            None if direction == Direction::Down => return Err("Entry not found in map".to_owned()),
+            None => return Err("Exit not found in map".to_owned()),
+        };
+        let mut y = match direction {
+            Direction::Down => 0,
+            Direction::Up => self.height,
+        };
+        let mut steps = vec![];
+        let mut portals_taken = HashSet::new();
+
+        match direction {
+            Direction::Down => {
+                while y < self.height {
+                    x = self.next_column_for_coords(x, y)?;
+                    steps.push((y, x));
+                    if self.is_blocked(x, y)? {
+                        return Ok((steps, Outcome::Trapped { line: y, column: x }));
+                    }
+                    // The walk only depends on the portal taken, so taking one twice means walking forever
+                    if self.is_portal(x, y)? && !portals_taken.insert((x, y)) {
+                        return Err(format!(
+                            "Entry {} loops forever through the portal on line {} column {}",
+                            label,
+                            y + 1,
+                            x + 1
+                        ));
+                    }
+                    y = self.next_line_for_coords(x, y)?;
+                }
+            }
+            Direction::Up => {
+                while y > 0 {
+                    y -= 1;
+                    if self.is_blocked(x, y)? {
+                        return Ok((steps, Outcome::Trapped { line: y, column: x }));
+                    }
+                    x = self.next_column_for_coords(x, y)?;
+                    steps.push((y, x));
+                }
+            }
+        }
+        finishes
+            .get(x)
+            .map(|finish| (steps, Outcome::Exit(finish.to_owned())))
+            .ok_or(match direction {
+                Direction::Down => "Exit not found in map".to_owned(),
+                Direction::Up => "Entry not found in map".to_owned(),
+            })
+    }
+
+    /// Returns the column the path of the provided entry is on at each line, starting with the entry column.
+    /// The trace of a trapped walker stops with the column of the obstacle.
+    /// With portals, lines are not walked in order: the lines come with walk_for_entry.
+    pub fn trace_for_entry(&self, entry: &str) -> Result<Vec<usize>, String> {
+        let (start, _) = self.starting_coords_for_entry(entry)?;
+        let (steps, _) = self.walk_for_entry(entry)?;
+
+        Ok([start]
+            .into_iter()
+            .chain(steps.iter().map(|(_, x)| *x))
+            .collect())
+    }
+
+    /// Walks the provided entry down to its exit or to the obstacle trapping it.
+    pub fn outcome_for_entry(&self, entry: &str) -> Result<Outcome, String> {
+        Ok(self.walk_for_entry(entry)?.1)
+    }
+
     /// Find the exit associated with the providfed entry.
     pub fn exit_for_entry(&self, entry: &str) -> Result<String, String> {
-        let mut coords = self.starting_coords_for_entry(entry)?;
+        match self.outcome_for_entry(entry)? {
+            Outcome::Exit(exit) => Ok(exit),
+            trapped => Err(format!("Entry {} is {}", entry, trapped)),
+        }
+    }
+
+    /// Returns, for each column of the line, if it is linked to the column on its right.
+    /// The last value is the wrap rung, only set on circular maps.
+    pub fn rungs_for_line(&self, y: usize) -> Result<Vec<bool>, String> {
+        (0..self.column_count(y)?)
+            .map(|x| Ok(self.can_go_to_sides_for_coords(x, y)?.1))
+            .collect()
+    }
+
+    /// Returns the characters in between the column and the column on its right.
+    /// For the last column, the characters after it are followed by the ones before the first column.
+    pub fn rung_segment(&self, x: usize, y: usize) -> Result<Vec<char>, String> {
+        let line = self
+            .content
+            .get(y)
+            .ok_or("Map has not enough lines for the required coordinates")?;
+        let (left, _) = self.content_coords_for_coords(x, y)?;
+
+        Ok(match self.content_coords_for_coords(x + 1, y) {
+            Ok((right, _)) => line[left + 1..right].to_vec(),
+            Err(_) => {
+                let (first, _) = self.content_coords_for_coords(0, y)?;
+
+                line[left + 1..]
+                    .iter()
+                    .chain(&line[..first])
+                    .copied()
+                    .collect()
+            }
+        })
+    }
+
+    /// Reads the weight written inside a rung segment: `-3-` costs 3 and `-+3-` is a bonus of 3.
+    /// Returns `None` if the segment is not a weighted rung.
+    pub fn weight_in_segment(segment: &[char]) -> Option<i64> {
+        let text = segment.iter().collect::<String>();
+        let weight = text.strip_prefix('-')?.strip_suffix('-')?.trim_matches('-');
+        let (sign, digits) = match weight.strip_prefix('+') {
+            Some(digits) => (-1, digits),
+            None => (1, weight),
+        };
+
+        if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+            digits.parse::<i64>().ok().map(|weight| sign * weight)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the weight of the rung right of the column (0 if it has none or if there is no rung).
+    pub fn rung_weight(&self, x: usize, y: usize) -> Result<i64, String> {
+        if self.can_go_to_sides_for_coords(x, y)?.1 {
+            Ok(Map::weight_in_segment(&self.rung_segment(x, y)?).unwrap_or(0))
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Returns the rung taken on the line `y` to go from column `from` to column `to`, as the
+    /// column on its left (the last column for a wrap rung). `None` if the walker stays on its column.
+    pub fn crossed_rung(&self, y: usize, from: usize, to: usize) -> Result<Option<usize>, String> {
+        if from == to {
+            Ok(None)
+        } else if self.can_go_to_sides_for_coords(from, y)?.1 {
+            // Going right uses the rung of the column left, going left the one of the column reached
+            Ok(Some(from))
+        } else {
+            Ok(Some(to))
+        }
+    }
+
+    /// Returns the sum of the weights of the rungs on the path of the entry (up to its obstacle if trapped).
+    pub fn cost_for_entry(&self, entry: &str) -> Result<i64, String> {
+        let (mut from, _) = self.starting_coords_for_entry(entry)?;
+        let mut cost = 0;
+
+        for (y, to) in self.walk_for_entry(entry)?.0 {
+            if let Some(x) = self.crossed_rung(y, from, to)? {
+                cost += self.rung_weight(x, y)?;
+            }
+            from = to;
+        }
+        Ok(cost)
+    }
+
+    /// Replaces the line with the provided rungs (cf. rungs_for_line).
+    pub fn set_rungs_for_line(&mut self, y: usize, rungs: &[bool]) -> Result<(), String> {
+        if y < self.content.len() {
+            self.content[y] = Map::line_for_rungs(rungs, self.circular);
+            self.permutation = None;
+            Ok(())
+        } else {
+            Err("Map has not enough lines for the required coordinates".to_owned())
+        }
+    }
+
+    /// Builds a content line from rungs (cf. rungs_for_line).
+    pub fn line_for_rungs(rungs: &[bool], circular: bool) -> Vec<char> {
+        let wraps = circular && rungs.last() == Some(&true);
+        let mut line = vec![];
 
-        while coords.1 < self.height {
-            match self.can_go_to_sides_for_coords(coords.0, coords.1)? {
-                (true, _) => coords.0 -= 1,
-                (_, true) => coords.0 += 1,
-                _ => {}
+        if wraps {
+            line.push('-');
+        }
+        for (x, rung) in rungs.iter().enumerate() {
+            line.push('|');
+            if *rung && x + 1 < rungs.len() {
+                line.extend(['-', '-']);
             }
-            coords.1 += 1;
         }
-        self.exit_for_column(coords.0)
+        if wraps {
+            line.push('-');
+        }
+        line
+    }
+
+    /// Renders the content of the map with the provided trace (cf. trace_for_entry) marked with '*'.
+    pub fn render_trace(&self, trace: &[usize]) -> String {
+        let mut lines = vec![self.entries.join(" ")];
+
+        for (y, line) in self.content.iter().enumerate() {
+            let line = Map::render_line(line);
+
+            lines.push(match trace.get(y) {
+                Some(column) => Map::mark_column(&line, *column, '*'),
+                None => line,
+            });
+        }
+        lines.push(self.exits.join(" "));
+        lines.join("\n")
+    }
+
+    /// Replaces the n-th column of a rendered line with the provided marker.
+    pub fn mark_column(line: &str, column: usize, marker: char) -> String {
+        let mut count = 0;
+
+        line.chars()
+            .map(|c| {
+                if Map::is_pole(&c) {
+                    count += 1;
+                    if count - 1 == column {
+                        return marker;
+                    }
+                }
+                c
+            })
+            .collect()
+    }
+
+    /// Finds where the walk of every entry ends, in the order of the entries.
+    pub fn outcomes(&self) -> Result<Vec<(String, Outcome)>, String> {
+        self.entries
+            .iter()
+            .map(|entry| Ok((entry.to_owned(), self.outcome_for_entry(entry)?)))
+            .collect()
+    }
+
+    /// Finds the exit associated with every entry, in the order of the entries.
+    /// Fails if an entry is trapped (cf. outcomes).
+    pub fn solve(&self) -> Result<Vec<(String, String)>, String> {
+        self.entries
+            .iter()
+            .map(|entry| Ok((entry.to_owned(), self.exit_for_entry(entry)?)))
+            .collect()
+    }
+
+    /// Returns the number of lines of the text format below the size line: labels and layer headers included.
+    pub fn text_height(&self) -> usize {
+        self.height + self.layers.len() + 2
+    }
+
+    /// Renders the lines in between the entries and the exits, layer headers included.
+    pub fn text_lines(&self) -> Vec<String> {
+        let mut lines = vec![];
+
+        for y in 0..=self.height {
+            for layer in self.layers.iter().filter(|layer| layer.start == y) {
+                lines.push(layer.to_string());
+            }
+            if let Some(line) = self.content.get(y) {
+                lines.push(Map::render_line(line));
+            }
+        }
+        lines
+    }
+
+    /// Renders a content line, putting back a space in between columns that are not linked.
+    pub fn render_line(line: &[char]) -> String {
+        let mut rendered = String::new();
+
+        for (i, c) in line.iter().enumerate() {
+            rendered.push(*c);
+            if Map::is_pole(c) && line.get(i + 1).is_some_and(Map::is_pole) {
+                rendered.push(' ');
+            }
+        }
+        rendered
+    }
+}
+
+impl FromStr for Map {
+    type Err = String;
+
+    /// Parses a map in the text format read from the standard input.
+    /// Without size line, the map goes up to the first blank line (cf. from_lines).
+    fn from_str(s: &str) -> Result<Map, String> {
+        let mut lines = s.lines();
+        let header = lines.next().ok_or("Missing map size")?;
+
+        if !Map::is_header(header, lines.clone().next()) {
+            return Map::from_lines(
+                s.lines()
+                    .map(|l| l.trim_end().to_owned())
+                    .take_while(|l| !l.trim().is_empty())
+                    .collect(),
+            );
+        }
+
+        let (width, height) = Map::size_from_header(header)?;
+
+        Map::from_header_and_content(
+            width,
+            height,
+            lines
+                .take(height)
+                .map(|l| l.trim_end().to_owned())
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for Map {
+    /// Renders the map in the same text format it is read from (size line included).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.width, self.text_height())?;
+        writeln!(f, "{}", self.entries.join(" "))?;
+        for line in self.text_lines() {
+            writeln!(f, "{}", line)?;
+        }
+        writeln!(f, "{}", self.exits.join(" "))
     }
 }
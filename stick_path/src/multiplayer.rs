@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::map::{Map, Outcome};
+
+/// What happens when several walkers want the same rung on the same tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CollisionPolicy {
+    /// The walker of the first entry takes the rung, the others stay where they are for the tick.
+    #[default]
+    Wait,
+    /// Nobody takes the rung: every walker goes straight down its own column.
+    Bounce,
+    /// Every walker takes the rung, crossing each other as if alone.
+    Swap,
+}
+
+impl FromStr for CollisionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CollisionPolicy, String> {
+        match s {
+            "wait" => Ok(CollisionPolicy::Wait),
+            "bounce" => Ok(CollisionPolicy::Bounce),
+            "swap" => Ok(CollisionPolicy::Swap),
+            _ => Err(format!("Unknown collision policy {}", s)),
+        }
+    }
+}
+
+/// Where the walker of an entry finished and the tick it got there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arrival {
+    pub entry: String,
+    pub outcome: Outcome,
+    pub tick: usize,
+}
+
+impl fmt::Display for Arrival {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.outcome {
+            Outcome::Exit(exit) => write!(f, "{}{} at tick {}", self.entry, exit, self.tick),
+            trapped => write!(f, "{} {} at tick {}", self.entry, trapped, self.tick),
+        }
+    }
+}
+
+/// Walks every entry down at the same time, each walker going down one line per tick (portals included).
+/// Walkers wanting the same rung on the same tick follow the collision policy.
+/// Returns the arrivals in the order of the entries, or an error if the walkers loop forever through portals.
+pub fn play(map: &Map, policy: CollisionPolicy) -> Result<Vec<Arrival>, String> {
+    // Line about to be walked and column of each walker still on the map
+    let mut positions: Vec<Option<(usize, usize)>> = (0..map.entries.len())
+        .map(|x| (map.height > 0).then_some((0, x)))
+        .collect();
+    let mut arrivals: Vec<Option<Arrival>> = (0..map.entries.len())
+        .map(|x| {
+            (map.height == 0)
+                .then(|| {
+                    Ok::<_, String>(Arrival {
+                        entry: map.entries[x].to_owned(),
+                        outcome: Outcome::Exit(map.exit_for_column(x)?),
+                        tick: 0,
+                    })
+                })
+                .transpose()
+        })
+        .collect::<Result<_, _>>()?;
+    let mut seen = HashSet::new();
+    let mut tick = 0;
+
+    while positions.iter().any(Option::is_some) {
+        // Ticks only depend on the positions, so positions seen before mean walking forever
+        if !seen.insert(positions.clone()) {
+            return Err("Walkers loop forever through portals".to_owned());
+        }
+        tick += 1;
+
+        // Column reached through the rung of the line and the rung taken, for each walker
+        let moves = positions
+            .iter()
+            .map(|position| {
+                position
+                    .map(|(y, x)| {
+                        let next = map.next_column_for_coords(x, y)?;
+
+                        Ok((next, map.crossed_rung(y, x, next)?.map(|rung| (y, rung))))
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        for (i, walker_move) in moves.iter().enumerate() {
+            let ((y, x), (next, rung)) = match (positions[i], walker_move) {
+                (Some(position), Some(walker_move)) => (position, *walker_move),
+                _ => continue,
+            };
+            // Walkers taking the same rung as this one, itself included
+            let contenders: Vec<usize> = match rung {
+                Some(_) => (0..moves.len())
+                    .filter(|&j| moves[j].is_some_and(|(_, other)| other == rung))
+                    .collect(),
+                None => vec![i],
+            };
+            let column = match policy {
+                _ if contenders.len() == 1 => next,
+                CollisionPolicy::Swap => next,
+                CollisionPolicy::Bounce => x,
+                CollisionPolicy::Wait if contenders[0] == i => next,
+                CollisionPolicy::Wait => continue,
+            };
+
+            if map.is_blocked(column, y)? {
+                positions[i] = None;
+                arrivals[i] = Some(Arrival {
+                    entry: map.entries[i].to_owned(),
+                    outcome: Outcome::Trapped { line: y, column },
+                    tick,
+                });
+                continue;
+            }
+
+            let line = map.next_line_for_coords(column, y)?;
+
+            if line < map.height {
+                positions[i] = Some((line, column));
+            } else {
+                positions[i] = None;
+                arrivals[i] = Some(Arrival {
+                    entry: map.entries[i].to_owned(),
+                    outcome: Outcome::Exit(map.exit_for_column(column)?),
+                    tick,
+                });
+            }
+        }
+    }
+    Ok(arrivals.into_iter().flatten().collect())
+}
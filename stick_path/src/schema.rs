@@ -0,0 +1,125 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::map::{Map, Outcome};
+
+/// Version of the serialized forms below, bumped on any change that older readers can not handle.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Serialized form of a `Map`: its lines are kept in the text format (cf. Map::text_lines),
+/// so that any change to the in-memory representation leaves stored maps readable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MapSchema {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "version"))]
+    pub version: u32,
+    pub width: usize,
+    /// Number of lines in between the entries and the exits, layer headers included.
+    pub height: usize,
+    pub circular: bool,
+    pub entries: Vec<String>,
+    pub exits: Vec<String>,
+    pub lines: Vec<String>,
+}
+
+impl From<Map> for MapSchema {
+    fn from(map: Map) -> MapSchema {
+        MapSchema {
+            version: SCHEMA_VERSION,
+            width: map.width,
+            height: map.text_height() - 2,
+            circular: map.circular,
+            lines: map.text_lines(),
+            entries: map.entries,
+            exits: map.exits,
+        }
+    }
+}
+
+impl TryFrom<MapSchema> for Map {
+    type Error = String;
+
+    /// Rebuilds the map through the parser, so a stored map can not hold what a text map could not.
+    fn try_from(schema: MapSchema) -> Result<Map, String> {
+        if schema.version != SCHEMA_VERSION {
+            return Err(format!("Unsupported schema version {}", schema.version));
+        }
+        if schema.lines.len() != schema.height {
+            return Err("Map height does not match its number of lines".to_owned());
+        }
+        if schema
+            .entries
+            .iter()
+            .chain(&schema.exits)
+            .any(|label| label.is_empty() || label.contains(char::is_whitespace))
+        {
+            return Err("Labels must be non-empty and without whitespace".to_owned());
+        }
+
+        let mut content = vec![schema.entries.join(" ")];
+
+        content.extend(schema.lines);
+        content.push(schema.exits.join(" "));
+
+        let mut map = Map::from_size_and_content(schema.width, schema.height + 2, content)?;
+
+        map.circular = schema.circular;
+        Ok(map)
+    }
+}
+
+/// Walk of one entry: the column it is on at the bottom of each line, and where it ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntryTrace {
+    pub entry: String,
+    /// Same as Map::trace_for_entry: stops on the line of the obstacle trapping the entry.
+    pub columns: Vec<usize>,
+    pub outcome: Outcome,
+}
+
+/// Serialized result of solving a map: the walk of every entry, in the order of the entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Solution {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "version"))]
+    pub version: u32,
+    pub traces: Vec<EntryTrace>,
+}
+
+impl Solution {
+    /// Solves the map, keeping the trace of every entry.
+    pub fn for_map(map: &Map) -> Result<Solution, String> {
+        let traces = map
+            .entries
+            .iter()
+            .map(|entry| {
+                Ok(EntryTrace {
+                    entry: entry.to_owned(),
+                    columns: map.trace_for_entry(entry)?,
+                    outcome: map.outcome_for_entry(entry)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Solution {
+            version: SCHEMA_VERSION,
+            traces,
+        })
+    }
+}
+
+/// Reads a schema version, rejecting the ones this build does not know.
+#[cfg(feature = "serde")]
+fn version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+
+    if version == SCHEMA_VERSION {
+        Ok(version)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "Unsupported schema version {}",
+            version
+        )))
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use crate::map::Map;
+
+/// Rung on the path of an entry, with the other entries walking through it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriticalRung {
+    pub line: usize,
+    /// Column on the left of the rung (the last column for a wrap rung).
+    pub column: usize,
+    pub shared_with: Vec<String>,
+}
+
+impl fmt::Display for CriticalRung {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Rung on line {} right of column {}",
+            self.line + 1,
+            self.column + 1
+        )?;
+        if !self.shared_with.is_empty() {
+            write!(f, ", shared with {}", self.shared_with.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Entries that would end their walk elsewhere (another exit or obstacle) if a rung were removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RungSensitivity {
+    pub line: usize,
+    /// Column on the left of the rung (the last column for a wrap rung).
+    pub column: usize,
+    pub changed: Vec<String>,
+}
+
+impl fmt::Display for RungSensitivity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Rung on line {} right of column {}: ",
+            self.line + 1,
+            self.column + 1
+        )?;
+        if self.changed.is_empty() {
+            write!(f, "no exit changes")
+        } else {
+            write!(f, "{} change exit", self.changed.join(" "))
+        }
+    }
+}
+
+impl Map {
+    /// Returns every rung on the path of the entry, from top to bottom.
+    pub fn critical_rungs(&self, entry: &str) -> Result<Vec<CriticalRung>, String> {
+        self.check_no_portals("Critical rungs")?;
+
+        let trace = self.trace_for_entry(entry)?;
+        let traces = self
+            .entries
+            .iter()
+            .map(|other| self.trace_for_entry(other))
+            .collect::<Result<Vec<_>, String>>()?;
+        let mut rungs = vec![];
+
+        for (y, columns) in trace.windows(2).enumerate() {
+            let to = columns[1];
+            let column = match self.crossed_rung(y, columns[0], to)? {
+                Some(column) => column,
+                None => continue,
+            };
+
+            rungs.push(CriticalRung {
+                line: y,
+                column,
+                shared_with: self
+                    .entries
+                    .iter()
+                    .zip(&traces)
+                    .filter(|(other, trace)| *other != entry && trace.get(y) == Some(&to))
+                    .map(|(other, _)| other.to_owned())
+                    .collect(),
+            });
+        }
+        Ok(rungs)
+    }
+}
+
+/// Returns, for each rung of the map, the entries that would end their walk elsewhere without it.
+/// The walks are the ones of the solver, so that entries trapped by an obstacle stay trapped.
+pub fn sensitivity(map: &Map) -> Result<Vec<RungSensitivity>, String> {
+    let mut map = map.clone();
+    let outcomes = map.outcomes()?;
+    let mut report = vec![];
+
+    for line in 0..map.height {
+        for (column, rung) in map.rungs_for_line(line)?.into_iter().enumerate() {
+            if !rung {
+                continue;
+            }
+            map.remove_rung(line, column)?;
+
+            let changed = map
+                .outcomes()?
+                .into_iter()
+                .zip(&outcomes)
+                .filter(|((_, after), (_, before))| after != before)
+                .map(|((entry, _), _)| entry)
+                .collect();
+
+            map.undo()?;
+            report.push(RungSensitivity {
+                line,
+                column,
+                changed,
+            });
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,594 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CliError, ErrorKind};
+use crate::generator::{self, Rng};
+use crate::map::{Map, Outcome};
+use crate::stream::MapReader;
+
+/// Largest size of the request line and headers together.
+const MAX_HEAD: u64 = 8 * 1024;
+
+/// Limits applied to every connection.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Largest request body accepted, in bytes.
+    pub max_body: usize,
+    /// Time allowed to each read and write on a connection.
+    pub timeout: Duration,
+    /// Time allowed to receive the whole request, however the client spreads its bytes over time.
+    pub deadline: Duration,
+    /// Number of connections answered at the same time, the next ones waiting to be accepted.
+    pub workers: usize,
+    /// Largest number of cells (columns times lines) of a generated map.
+    pub max_generated_cells: usize,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            max_body: 1024 * 1024,
+            timeout: Duration::from_secs(5),
+            deadline: Duration::from_secs(30),
+            workers: 8,
+            max_generated_cells: 1_000_000,
+        }
+    }
+}
+
+impl Config {
+    /// Checks that the limits leave room to answer requests.
+    pub fn check(&self) -> Result<(), String> {
+        if self.timeout.is_zero() {
+            Err("Timeout must be longer than zero".to_owned())
+        } else if self.deadline.is_zero() {
+            Err("Deadline must be longer than zero".to_owned())
+        } else if self.workers == 0 {
+            Err("Server needs at least one worker".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Body of a JSON request (cf. map_for_request).
+#[derive(Deserialize)]
+struct MapBody {
+    map: String,
+    #[serde(default)]
+    circular: bool,
+}
+
+/// Body of the errors of the HTTP layer itself.
+#[derive(Serialize)]
+struct HttpError<'a> {
+    kind: &'static str,
+    message: &'a str,
+}
+
+/// Where an entry of a solved map ends up, its exit or where it is trapped.
+#[derive(Serialize)]
+struct Solved<'a> {
+    entry: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exit: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trapped: Option<Trap>,
+}
+
+#[derive(Serialize)]
+struct Trap {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Serialize)]
+struct Exits<'a> {
+    exits: Vec<Solved<'a>>,
+}
+
+#[derive(Serialize)]
+struct Validity {
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Writes a response body as JSON.
+fn to_json(body: &impl Serialize) -> String {
+    // Bodies only hold strings, numbers and booleans, which always serialize
+    serde_json::to_string(body).unwrap_or_default()
+}
+
+/// HTTP request, as much of it as the endpoints need.
+#[derive(Debug, Clone, Default)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Header names are lowercased.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// HTTP response, always closing the connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(status: u16, body: String) -> Response {
+        Response {
+            status,
+            content_type: "application/json",
+            body,
+        }
+    }
+
+    /// Responds with the error as a JSON object (cf. CliError::to_json).
+    pub fn error(err: &CliError) -> Response {
+        let status = match err.kind {
+            ErrorKind::Usage | ErrorKind::Parse => 400,
+            ErrorKind::Io => 500,
+            ErrorKind::Validation | ErrorKind::Solve => 422,
+        };
+
+        Response::json(status, err.to_json())
+    }
+
+    /// Responds with an error of the HTTP layer itself (size limit, timeout...).
+    fn http_error(status: u16, message: &str) -> Response {
+        Response::json(
+            status,
+            to_json(&HttpError {
+                kind: "http",
+                message,
+            }),
+        )
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            411 => "Length Required",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+
+    /// Writes the response with its status line and headers.
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len(),
+            self.body
+        )?;
+        out.flush()
+    }
+}
+
+/// Decodes a `application/x-www-form-urlencoded` component ('+' and `%XX` escapes).
+fn decode_component(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], escaped) {
+            (b'+', _) => decoded.push(b' '),
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 2;
+            }
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Splits a request target into its path and its decoded query parameters.
+fn parse_target(target: &str) -> (String, Vec<(String, String)>) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            (decode_component(key), decode_component(value))
+        })
+        .collect();
+
+    (path.to_owned(), query)
+}
+
+/// Converts a read failure to the response sent back: a timeout or a malformed request.
+fn read_error(err: io::Error) -> Response {
+    match err.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            Response::http_error(408, "Request not received in time")
+        }
+        _ => Response::http_error(400, &format!("Could not read request: {}", err)),
+    }
+}
+
+/// Reads a request, its body being limited to `max_body` bytes.
+/// Returns the response to send back if the request can not be read.
+pub fn read_request(input: impl Read, max_body: usize) -> Result<Request, Response> {
+    let mut reader = BufReader::new(input);
+    let mut head = (&mut reader).take(MAX_HEAD);
+    let mut lines = vec![];
+
+    loop {
+        let mut line = String::new();
+
+        if head.read_line(&mut line).map_err(read_error)? == 0 {
+            return Err(if head.limit() == 0 {
+                Response::http_error(431, "Request head is too large")
+            } else {
+                Response::http_error(400, "Request ended before its body")
+            });
+        }
+        if line.trim_end().is_empty() {
+            break;
+        }
+        lines.push(line.trim_end().to_owned());
+    }
+
+    let mut request_line = lines.first().map_or("", |l| l.as_str()).split(' ');
+    let (method, target) = match (request_line.next(), request_line.next()) {
+        (Some(method), Some(target)) if !method.is_empty() => (method, target),
+        _ => return Err(Response::http_error(400, "Invalid request line")),
+    };
+    let (path, query) = parse_target(target);
+    let headers = lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_lowercase(), value.trim().to_owned()))
+        .collect::<Vec<_>>();
+    let mut request = Request {
+        method: method.to_owned(),
+        path,
+        query,
+        headers,
+        body: vec![],
+    };
+
+    if request.header("transfer-encoding").is_some() {
+        return Err(Response::http_error(411, "Body must have a Content-Length"));
+    }
+
+    let length = match request.header("content-length") {
+        Some(length) => length
+            .parse::<usize>()
+            .map_err(|_| Response::http_error(400, "Invalid Content-Length"))?,
+        None => 0,
+    };
+
+    if length > max_body {
+        return Err(Response::http_error(
+            413,
+            &format!("Body is limited to {} bytes", max_body),
+        ));
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body).map_err(read_error)?;
+    Ok(request)
+}
+
+/// Reads the map of a request: the body itself, or the `map` member of a JSON body along with `circular`.
+/// For text bodies, circular maps are requested with `?circular=true`.
+fn map_for_request(request: &Request) -> Result<Map, CliError> {
+    let body = std::str::from_utf8(&request.body)
+        .map_err(|_| CliError::parse("Body is not valid UTF-8"))?;
+    let is_json = request
+        .header("content-type")
+        .is_some_and(|content_type| content_type.starts_with("application/json"));
+    let (text, circular) = if is_json {
+        let body = serde_json::from_str::<MapBody>(body)
+            .map_err(|e| CliError::parse(format!("Invalid JSON body: {}", e)))?;
+
+        (body.map, body.circular)
+    } else {
+        (body.to_owned(), request.query("circular") == Some("true"))
+    };
+    let mut map = MapReader::new(text.as_bytes())
+        .next()
+        .ok_or(CliError::parse("Missing map size"))??;
+
+    map.circular = circular;
+    Ok(map)
+}
+
+/// Reads and validates the map of a request.
+fn valid_map_for_request(request: &Request) -> Result<Map, CliError> {
+    let map = map_for_request(request)?;
+
+    map.validate().map_err(CliError::validation)?;
+    Ok(map)
+}
+
+/// `POST /solve`: the exit of every entry, or where it is trapped.
+fn solve(request: &Request) -> Result<Response, CliError> {
+    let map = valid_map_for_request(request)?;
+    let outcomes = map.outcomes().map_err(CliError::solve)?;
+    let exits = outcomes
+        .iter()
+        .map(|(entry, outcome)| match outcome {
+            Outcome::Exit(exit) => Solved {
+                entry,
+                exit: Some(exit),
+                trapped: None,
+            },
+            Outcome::Trapped { line, column } => Solved {
+                entry,
+                exit: None,
+                trapped: Some(Trap {
+                    line: line + 1,
+                    column: column + 1,
+                }),
+            },
+        })
+        .collect();
+
+    Ok(Response::json(200, to_json(&Exits { exits })))
+}
+
+/// `POST /validate`: whether the map follows the rules, with the reason if it does not.
+fn validate(request: &Request) -> Result<Response, CliError> {
+    let map = map_for_request(request)?;
+
+    let error = map.validate().err();
+
+    Ok(Response::json(
+        200,
+        to_json(&Validity {
+            valid: error.is_none(),
+            error,
+        }),
+    ))
+}
+
+/// `POST /render`: the map as an SVG image (cf. Map::to_svg).
+fn render(request: &Request) -> Result<Response, CliError> {
+    let map = valid_map_for_request(request)?;
+
+    Ok(Response {
+        status: 200,
+        content_type: "image/svg+xml",
+        body: map.to_svg().map_err(CliError::solve)?,
+    })
+}
+
+/// `GET /generate?columns=C&height=H[&density=D][&circular=true][&seed=N]`: a random valid map, in the text format.
+fn generate(request: &Request, config: &Config) -> Result<Response, CliError> {
+    fn param<T: std::str::FromStr>(request: &Request, name: &str) -> Result<Option<T>, CliError> {
+        request
+            .query(name)
+            .map(|value| {
+                value
+                    .parse::<T>()
+                    .map_err(|_| CliError::usage(format!("Invalid {} parameter", name)))
+            })
+            .transpose()
+    }
+    let usage = || CliError::usage("Usage: /generate?columns=C&height=H[&density=D][&seed=N]");
+    let columns = param::<usize>(request, "columns")?.ok_or_else(usage)?;
+    let height = param::<usize>(request, "height")?.ok_or_else(usage)?;
+    let density = param::<f64>(request, "density")?.unwrap_or(0.5);
+    let circular = param::<bool>(request, "circular")?.unwrap_or(false);
+    let mut rng = param::<u64>(request, "seed")?
+        .map(Rng::new)
+        .unwrap_or_else(Rng::from_time);
+
+    if columns == 0 || !(0.0..=1.0).contains(&density) {
+        return Err(usage());
+    }
+    if columns
+        .checked_mul(height)
+        .is_none_or(|cells| cells > config.max_generated_cells)
+    {
+        return Err(CliError::usage(format!(
+            "Generated maps are limited to {} cells",
+            config.max_generated_cells
+        )));
+    }
+
+    Ok(Response {
+        status: 200,
+        content_type: "text/plain",
+        body: generator::generate(columns, height, density, circular, &mut rng).to_string(),
+    })
+}
+
+/// Routes the request to its endpoint.
+pub fn handle(request: &Request, config: &Config) -> Response {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/solve") => solve(request),
+        ("POST", "/validate") => validate(request),
+        ("POST", "/render") => render(request),
+        ("GET", "/generate") => generate(request, config),
+        (_, "/solve" | "/validate" | "/render" | "/generate") => {
+            return Response::http_error(405, "Method not allowed")
+        }
+        _ => return Response::http_error(404, "Not found"),
+    };
+
+    result.unwrap_or_else(|err| Response::error(&err))
+}
+
+/// Connection read and written within a deadline: each read or write gives up once it is passed,
+/// on top of the timeout of each of them.
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+    timeout: Duration,
+}
+
+impl<'a> Deadline<'a> {
+    fn new(stream: &'a TcpStream, allowed: Duration, timeout: Duration) -> Deadline<'a> {
+        Deadline {
+            stream,
+            deadline: Instant::now() + allowed,
+            timeout,
+        }
+    }
+
+    /// Returns the timeout of the next read or write, an error once the deadline is passed.
+    fn next_timeout(&self) -> io::Result<Duration> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+
+        if left.is_zero() {
+            Err(io::Error::new(io::ErrorKind::TimedOut, "Deadline passed"))
+        } else {
+            Ok(left.min(self.timeout))
+        }
+    }
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.set_read_timeout(Some(self.next_timeout()?))?;
+        (&mut &*self.stream).read(buf)
+    }
+}
+
+impl Write for Deadline<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.set_write_timeout(Some(self.next_timeout()?))?;
+        (&mut &*self.stream).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&mut &*self.stream).flush()
+    }
+}
+
+/// Answers the single request of a connection. Receiving the request is limited by the deadline,
+/// writing the response and reading what the client still sends by a single timeout each.
+fn handle_connection(stream: TcpStream, config: &Config, handler: Handler) {
+    let input = Deadline::new(&stream, config.deadline, config.timeout);
+    let response = match read_request(input, config.max_body) {
+        // A panicking handler is answered as a server error, the worker going on with the next connections
+        Ok(request) => panic::catch_unwind(AssertUnwindSafe(|| handler(&request, config)))
+            .unwrap_or_else(|_| Response::http_error(500, "Internal server error")),
+        Err(response) => response,
+    };
+
+    if response
+        .write_to(&mut Deadline::new(&stream, config.timeout, config.timeout))
+        .is_ok()
+    {
+        // Closing with unread data would reset the connection before the client reads the response
+        let _ = stream.shutdown(std::net::Shutdown::Write);
+        let _ = io::copy(
+            &mut Deadline::new(&stream, config.timeout, config.timeout)
+                .take(config.max_body as u64),
+            &mut io::sink(),
+        );
+    }
+}
+
+/// Answers a request read by the server (cf. handle).
+pub type Handler = fn(&Request, &Config) -> Response;
+
+/// HTTP server answering connections with a fixed number of worker threads.
+pub struct Server {
+    listener: TcpListener,
+    config: Arc<Config>,
+    handler: Handler,
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, config: Config) -> Result<Server, String> {
+        config.check()?;
+        Ok(Server {
+            listener: TcpListener::bind(addr).map_err(|e| format!("Could not bind: {}", e))?,
+            config: Arc::new(config),
+            handler: handle,
+        })
+    }
+
+    /// Answers the requests with the provided handler instead of the routes of `handle`.
+    pub fn with_handler(self, handler: Handler) -> Server {
+        Server { handler, ..self }
+    }
+
+    /// Returns the address listened to, useful when bound to port 0.
+    pub fn local_addr(&self) -> Result<SocketAddr, String> {
+        self.listener.local_addr().map_err(|e| e.to_string())
+    }
+
+    /// Answers connections until the process ends. A connection is only accepted once a worker is
+    /// free to answer it, the next ones waiting in the backlog of the listener.
+    pub fn run(self) {
+        let (sender, receiver) = mpsc::sync_channel::<TcpStream>(0);
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for _ in 0..self.config.workers {
+            let receiver = Arc::clone(&receiver);
+            let config = Arc::clone(&self.config);
+            let handler = self.handler;
+
+            thread::spawn(move || loop {
+                // The lock is released as soon as a connection is received
+                let stream = match receiver.lock() {
+                    Ok(receiver) => receiver.recv(),
+                    Err(_) => return,
+                };
+
+                match stream {
+                    Ok(stream) => handle_connection(stream, &config, handler),
+                    Err(_) => return,
+                }
+            });
+        }
+        for stream in self.listener.incoming().flatten() {
+            if sender.send(stream).is_err() {
+                return;
+            }
+        }
+    }
+}
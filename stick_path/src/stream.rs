@@ -0,0 +1,116 @@
+use std::fmt;
+use std::io::BufRead;
+
+use crate::map::Map;
+
+/// Failure to read a map: the input could not be read or did not hold a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadError {
+    Io(String),
+    Parse(String),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(message) | ReadError::Parse(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Reads maps one after the other from a text stream, blank lines in between them being skipped.
+/// Maps are not validated. A map without size line goes up to the next blank line (cf. Map::from_lines).
+pub struct MapReader<R: BufRead> {
+    input: R,
+    /// Line read ahead to tell a size line from numeric labels (cf. Map::is_header), read again first.
+    ahead: Option<String>,
+    finished: bool,
+}
+
+impl<R: BufRead> MapReader<R> {
+    pub fn new(input: R) -> MapReader<R> {
+        MapReader {
+            input,
+            ahead: None,
+            finished: false,
+        }
+    }
+
+    /// Reads a line, without its line ending. Returns `None` at the end of the input.
+    fn read_line(&mut self) -> Result<Option<String>, ReadError> {
+        match self.ahead.take() {
+            Some(line) => Ok(Some(line)),
+            None => self.read_input_line(),
+        }
+    }
+
+    /// Reads a line from the input itself, skipping the line read ahead.
+    fn read_input_line(&mut self) -> Result<Option<String>, ReadError> {
+        let mut line = String::new();
+
+        match self.input.read_line(&mut line) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(line.trim_end().to_owned())),
+            Err(e) => {
+                self.finished = true;
+                Err(ReadError::Io(format!(
+                    "Could not read line from input: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// Reads the next map without size line, from its first line up to the next blank line.
+    fn read_headerless_map(&mut self, first: String) -> Result<Map, ReadError> {
+        let mut lines = vec![first];
+
+        while let Some(line) = self.read_line()? {
+            if line.trim().is_empty() {
+                break;
+            }
+            lines.push(line);
+        }
+        Map::from_lines(lines).map_err(ReadError::Parse)
+    }
+
+    /// Reads the next map from its first line, with or without size line.
+    fn read_map(&mut self, first: String) -> Result<Map, ReadError> {
+        // The line after a size line is the entries line, without poles
+        if self.ahead.is_none() {
+            self.ahead = self.read_input_line()?;
+        }
+        if !Map::is_header(&first, self.ahead.as_deref()) {
+            return self.read_headerless_map(first);
+        }
+
+        let (width, height) = Map::size_from_header(&first).map_err(ReadError::Parse)?;
+        let mut content = vec![];
+
+        while content.len() < height {
+            match self.read_line()? {
+                Some(line) => content.push(line),
+                None => break,
+            }
+        }
+        Map::from_header_and_content(width, height, content).map_err(ReadError::Parse)
+    }
+}
+
+impl<R: BufRead> Iterator for MapReader<R> {
+    type Item = Result<Map, ReadError>;
+
+    fn next(&mut self) -> Option<Result<Map, ReadError>> {
+        if self.finished {
+            return None;
+        }
+        loop {
+            match self.read_line() {
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(Some(first)) => return Some(self.read_map(first)),
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+
+use crate::map::Map;
+
+/// A rung added or removed in between a column and the column on its right (the first one for the last column).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RungChange {
+    pub line: usize,
+    pub column: usize,
+    pub added: bool,
+}
+
+impl fmt::Display for RungChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rung on line {} right of column {}",
+            if self.added { "Add" } else { "Remove" },
+            self.line + 1,
+            self.column + 1
+        )
+    }
+}
+
+/// Smallest set of changes making an entry reach the wanted exit, with the resulting map and traces.
+#[derive(Debug)]
+pub struct Suggestion {
+    pub changes: Vec<RungChange>,
+    pub before: Vec<usize>,
+    pub after: Vec<usize>,
+    pub map: Map,
+}
+
+/// One way of going through a line: the column reached and the changes needed.
+struct Move {
+    column: usize,
+    changes: Vec<RungChange>,
+}
+
+/// Cheapest way found to reach a column at the top of a line.
+struct Best {
+    cost: usize,
+    previous: usize,
+    changes: Vec<RungChange>,
+}
+
+/// Returns the slot of the rung on the left of the column, if any (cf. Map.rungs_for_line).
+fn left_slot(x: usize, columns: usize, circular: bool) -> Option<usize> {
+    if x > 0 {
+        Some(x - 1)
+    } else if circular && columns > 1 {
+        Some(columns - 1)
+    } else {
+        None
+    }
+}
+
+/// Returns the slot of the rung on the right of the column, if any (cf. Map.rungs_for_line).
+fn right_slot(x: usize, columns: usize, circular: bool) -> Option<usize> {
+    if x + 1 < columns || (circular && columns > 1) {
+        Some(x)
+    } else {
+        None
+    }
+}
+
+/// Lists the ways of crossing the line `y` from column `x` with the changes they need.
+/// Taking a rung requires its neighbours to be removed so the line stays free of conflicting rungs.
+/// Ways ending on a broken pole (cf. OBSTACLE) are left out, the walker would be trapped there.
+fn moves_for_line(map: &Map, rungs: &[bool], x: usize, y: usize) -> Result<Vec<Move>, String> {
+    let columns = rungs.len();
+    let circular = map.circular;
+    let change = |column: usize, added: bool| RungChange {
+        line: y,
+        column,
+        added,
+    };
+    let removals = |slots: &[Option<usize>]| {
+        slots
+            .iter()
+            .flatten()
+            .filter(|slot| rungs[**slot])
+            .map(|slot| change(*slot, false))
+            .collect::<Vec<_>>()
+    };
+    let left = left_slot(x, columns, circular);
+    let right = right_slot(x, columns, circular);
+    let mut moves = vec![
+        Move {
+            column: map.next_column_for_coords(x, y)?,
+            changes: vec![],
+        },
+        Move {
+            column: x,
+            changes: removals(&[left, right]),
+        },
+    ];
+
+    for (slot, column) in [
+        (left, left.map(|_| (x + columns - 1) % columns)),
+        (right, right.map(|_| (x + 1) % columns)),
+    ] {
+        if let (Some(slot), Some(column)) = (slot, column) {
+            let mut changes = removals(&[
+                left_slot(slot, columns, circular),
+                right_slot((slot + 1) % columns, columns, circular),
+            ]);
+
+            if !rungs[slot] {
+                changes.push(change(slot, true));
+            }
+            moves.push(Move { column, changes });
+        }
+    }
+
+    let mut open = vec![];
+
+    for step in moves {
+        if !map.is_blocked(step.column, y)? {
+            open.push(step);
+        }
+    }
+    Ok(open)
+}
+
+/// Finds the smallest set of rungs to add or remove so that the entry reaches the provided exit,
+/// avoiding obstacles. The changes are made through the edit API, keeping the other characters of the lines.
+pub fn suggest(map: &Map, entry: &str, exit: &str) -> Result<Suggestion, String> {
+    map.validate()?;
+    map.check_no_portals("Suggestions")?;
+
+    let (start, _) = map.starting_coords_for_entry(entry)?;
+    let target = map
+        .exits
+        .iter()
+        .position(|e| e == exit)
+        .ok_or("Exit not found in map")?;
+    let columns = map.entries.len();
+    // Cheapest changes (and previous column) to reach each column at the top of each line
+    let mut best: Vec<Vec<Option<Best>>> = (0..=map.height)
+        .map(|_| (0..columns).map(|_| None).collect())
+        .collect();
+
+    best[0][start] = Some(Best {
+        cost: 0,
+        previous: start,
+        changes: vec![],
+    });
+    for y in 0..map.height {
+        let rungs = map.rungs_for_line(y)?;
+
+        for x in 0..columns {
+            let cost = match &best[y][x] {
+                Some(best) => best.cost,
+                None => continue,
+            };
+
+            for step in moves_for_line(map, &rungs, x, y)? {
+                let step_cost = cost + step.changes.len();
+
+                if best[y + 1][step.column]
+                    .as_ref()
+                    .is_none_or(|best| step_cost < best.cost)
+                {
+                    best[y + 1][step.column] = Some(Best {
+                        cost: step_cost,
+                        previous: x,
+                        changes: step.changes,
+                    });
+                }
+            }
+        }
+    }
+
+    // Walks back from the wanted exit to collect the changes
+    let mut changes = vec![];
+    let mut after = vec![target];
+    let mut x = target;
+
+    for y in (1..=map.height).rev() {
+        let step = best
+            .get(y)
+            .and_then(|line| line.get(x))
+            .and_then(Option::as_ref)
+            .ok_or("Exit can not be reached from entry")?;
+
+        changes.extend(step.changes.iter().copied());
+        x = step.previous;
+        after.push(x);
+    }
+    changes.sort_by_key(|change| (change.line, change.column));
+    after.reverse();
+
+    let mut edited = map.clone();
+
+    // Removed rungs first, so that added ones never conflict with them
+    for change in changes.iter().filter(|change| !change.added) {
+        edited.remove_rung(change.line, change.column)?;
+    }
+    for change in changes.iter().filter(|change| change.added) {
+        edited.add_rung(change.line, change.column)?;
+    }
+    Ok(Suggestion {
+        changes,
+        before: map.trace_for_entry(entry)?,
+        after,
+        map: edited,
+    })
+}
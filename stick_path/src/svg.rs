@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use crate::map::Map;
+
+/// Horizontal space in between two columns.
+const COLUMN_SPACING: usize = 40;
+/// Vertical space in between two lines.
+const LINE_SPACING: usize = 30;
+/// Space around the ladder, holding the labels.
+const MARGIN: usize = 30;
+
+/// Escapes the characters that can not appear as is in XML text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Map {
+    /// Renders the map as an SVG image: entries on top, exits at the bottom, one horizontal segment per rung
+    /// with its weight if any, wrap rungs leaving both edges, obstacles drawn as red crosses and portals as blue circles.
+    pub fn to_svg(&self) -> Result<String, String> {
+        let columns = self.entries.len().max(self.exits.len());
+        let width = 2 * MARGIN + columns.saturating_sub(1) * COLUMN_SPACING;
+        let bottom = MARGIN + (self.height + 1) * LINE_SPACING;
+        let height = bottom + MARGIN;
+        let column_x = |x: usize| MARGIN + x * COLUMN_SPACING;
+        let line_y = |y: usize| MARGIN + (y + 1) * LINE_SPACING;
+        let mut svg = String::new();
+
+        // Writing to a String can not fail
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+            width, height, width, height
+        );
+        let _ = writeln!(
+            svg,
+            "<g stroke=\"black\" stroke-width=\"2\" stroke-linecap=\"round\">"
+        );
+        for x in 0..columns {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\"/>",
+                column_x(x),
+                MARGIN,
+                bottom
+            );
+        }
+        for y in 0..self.height {
+            let rungs = self.rungs_for_line(y)?;
+
+            for (x, _) in rungs.iter().enumerate().filter(|(_, rung)| **rung) {
+                if x + 1 < rungs.len() {
+                    let _ = writeln!(
+                        svg,
+                        "<line x1=\"{}\" y1=\"{2}\" x2=\"{}\" y2=\"{2}\"/>",
+                        column_x(x),
+                        column_x(x + 1),
+                        line_y(y)
+                    );
+                } else {
+                    let _ = writeln!(
+                        svg,
+                        "<line x1=\"{}\" y1=\"{2}\" x2=\"{}\" y2=\"{2}\"/>",
+                        column_x(x),
+                        width,
+                        line_y(y)
+                    );
+                    let _ = writeln!(
+                        svg,
+                        "<line x1=\"0\" y1=\"{1}\" x2=\"{0}\" y2=\"{1}\"/>",
+                        column_x(0),
+                        line_y(y)
+                    );
+                }
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+
+        let _ = writeln!(
+            svg,
+            "<g font-family=\"monospace\" font-size=\"14\" text-anchor=\"middle\">"
+        );
+        for (x, entry) in self.entries.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\">{}</text>",
+                column_x(x),
+                MARGIN - 10,
+                escape(entry)
+            );
+        }
+        for (x, exit) in self.exits.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\">{}</text>",
+                column_x(x),
+                bottom + 20,
+                escape(exit)
+            );
+        }
+        for y in 0..self.height {
+            for x in 0..self.column_count(y)? {
+                let weight = self.rung_weight(x, y)?;
+
+                if weight != 0 {
+                    let _ = writeln!(
+                        svg,
+                        "<text x=\"{}\" y=\"{}\" font-size=\"10\">{}</text>",
+                        column_x(x) + COLUMN_SPACING / 2,
+                        line_y(y) - 4,
+                        weight
+                    );
+                }
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+
+        let _ = writeln!(svg, "<g stroke=\"red\" stroke-width=\"2\">");
+        for y in 0..self.height {
+            for x in 0..self.column_count(y)? {
+                let (cx, cy) = (column_x(x), line_y(y) + LINE_SPACING / 2);
+
+                if self.is_blocked(x, y)? {
+                    let _ = writeln!(
+                        svg,
+                        "<path d=\"M{} {} l10 10 m0 -10 l-10 10\"/>",
+                        cx - 5,
+                        cy - 5
+                    );
+                } else if self.is_portal(x, y)? {
+                    let _ = writeln!(
+                        svg,
+                        "<circle cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"white\" stroke=\"blue\"/>",
+                        cx, cy
+                    );
+                }
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+        let _ = writeln!(svg, "</svg>");
+        Ok(svg)
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use crate::animation::{Animation, Key};
+    use crate::map::Map;
+
+    fn simple_map() -> Map {
+        Map::from_size_and_content(
+            5,
+            5,
+            vec![
+                "A  B  C".to_owned(),
+                "|--|  |".to_owned(),
+                "|  |  |".to_owned(),
+                "|  |--|".to_owned(),
+                "1  2  3".to_owned(),
+            ],
+        )
+        .expect("Could not create map")
+    }
+
+    mod step {
+        use super::*;
+
+        #[test]
+        fn follows_the_same_path_as_exit_for_entry() {
+            let map = simple_map();
+
+            for (i, entry) in map.entries.iter().enumerate() {
+                let mut animation = Animation::new(&map, i).expect("Could not create animation");
+
+                while !animation.is_finished() {
+                    animation.step().expect("Could not step");
+                }
+                assert_eq!(animation.path, map.trace_for_entry(entry).unwrap());
+                assert_eq!(animation.exit(), map.exit_for_entry(entry).ok());
+            }
+        }
+
+        #[test]
+        fn does_nothing_once_finished() {
+            let map = simple_map();
+            let mut animation = Animation::new(&map, 0).expect("Could not create animation");
+
+            for _ in 0..10 {
+                animation.step().expect("Could not step");
+            }
+            assert_eq!(animation.line, 3);
+            assert_eq!(animation.path, vec![0, 1, 1, 2]);
+        }
+    }
+
+    mod handle_key {
+        use super::*;
+
+        #[test]
+        fn pause_stops_ticks() {
+            let map = simple_map();
+            let mut animation = Animation::new(&map, 0).expect("Could not create animation");
+
+            assert_eq!(animation.handle_key(Key::TogglePause), Ok(true));
+            animation.tick().expect("Could not tick");
+            assert_eq!(animation.line, 0);
+            animation.handle_key(Key::TogglePause).unwrap();
+            animation.tick().expect("Could not tick");
+            assert_eq!(animation.line, 1);
+        }
+
+        #[test]
+        fn step_pauses_and_moves_one_line() {
+            let map = simple_map();
+            let mut animation = Animation::new(&map, 0).expect("Could not create animation");
+
+            animation.handle_key(Key::Step).unwrap();
+            assert!(animation.paused);
+            assert_eq!(animation.line, 1);
+            assert_eq!(animation.column(), 1);
+        }
+
+        #[test]
+        fn switching_entry_restarts_the_walk() {
+            let map = simple_map();
+            let mut animation = Animation::new(&map, 0).expect("Could not create animation");
+
+            animation.step().unwrap();
+            animation.handle_key(Key::NextEntry).unwrap();
+            assert_eq!((animation.entry, animation.line), (1, 0));
+            animation.handle_key(Key::PreviousEntry).unwrap();
+            animation.handle_key(Key::PreviousEntry).unwrap();
+            assert_eq!((animation.entry, animation.line), (2, 0));
+        }
+
+        #[test]
+        fn quit_stops_the_viewer() {
+            let map = simple_map();
+            let mut animation = Animation::new(&map, 0).expect("Could not create animation");
+
+            assert_eq!(animation.handle_key(Key::Quit), Ok(false));
+        }
+    }
+
+    mod frame {
+        use super::*;
+
+        #[test]
+        fn shows_walked_path_and_walker() {
+            let map = simple_map();
+            let mut animation = Animation::new(&map, 0).expect("Could not create animation");
+
+            animation.step().unwrap();
+            animation.step().unwrap();
+            assert_eq!(
+                animation.frame(),
+                "A B C\n*--| |\n| * |\n| o--|\n1 2 3\n\nA - line 3/3"
+            );
+        }
+
+        #[test]
+        fn shows_the_exit_once_finished() {
+            let map = simple_map();
+            let mut animation = Animation::new(&map, 1).expect("Could not create animation");
+
+            for _ in 0..3 {
+                animation.step().unwrap();
+            }
+            assert!(animation.frame().ends_with("B reaches 1"));
+        }
+
+        #[test]
+        fn shows_where_the_walker_is_trapped() {
+            let map = "5 4\nA B C\n|--| |\n| x |\n1 2 3".parse::<Map>().unwrap();
+            let mut animation = Animation::new(&map, 0).expect("Could not create animation");
+
+            for _ in 0..3 {
+                animation.step().unwrap();
+            }
+            assert!(animation.is_finished());
+            assert_eq!(animation.exit(), None);
+            assert!(animation
+                .frame()
+                .ends_with("A is trapped on line 2 column 2"));
+        }
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use crate::batch::{map_paths, run, BatchResult};
+
+    /// Creates an empty directory in the temporary folder, unique to the test.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("stick_path_{}_{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Could not create directory");
+        dir
+    }
+
+    fn maps_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("maps")
+    }
+
+    #[test]
+    fn directory_maps_are_sorted() {
+        let paths = map_paths(&maps_dir()).expect("Could not list maps");
+        let names = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["huge_map.txt", "map_16_18.txt", "map_7_7.txt"]);
+    }
+
+    #[test]
+    fn manifest_paths_are_relative_to_the_manifest() {
+        let dir = temp_dir("manifest");
+        let manifest = dir.join("manifest");
+
+        fs::write(&manifest, "# maps\nfirst.txt\n\n  sub/second.txt  \n").unwrap();
+        assert_eq!(
+            map_paths(&manifest),
+            Ok(vec![dir.join("first.txt"), dir.join("sub/second.txt")])
+        );
+        assert!(map_paths(&dir.join("missing")).is_err());
+    }
+
+    #[test]
+    fn results_come_in_order_and_bad_maps_do_not_stop_the_batch() {
+        let dir = temp_dir("batch");
+        let mut paths = vec![];
+
+        for i in 0..20 {
+            let path = dir.join(format!("{:02}.txt", i));
+
+            if i % 7 == 3 {
+                fs::write(&path, "5 5\nA B\n|--|\n").unwrap();
+            } else {
+                fs::write(&path, "5 4\nA B\n|--|\n| |\n1 2\n").unwrap();
+            }
+            paths.push(path);
+        }
+        paths.push(dir.join("missing.txt"));
+
+        let mut results = vec![];
+
+        run(paths.clone(), 4, false, |result| results.push(result));
+        assert_eq!(
+            results.iter().map(|r| r.path.clone()).collect::<Vec<_>>(),
+            paths
+        );
+        for (i, result) in results.iter().enumerate() {
+            if i % 7 == 3 || i == 20 {
+                assert!(result.outcome.is_err());
+            } else {
+                assert_eq!(
+                    result.outcome,
+                    Ok(vec![
+                        ("A".to_owned(), "2".to_owned()),
+                        ("B".to_owned(), "1".to_owned())
+                    ])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn results_are_formatted_as_json_lines() {
+        let ok = BatchResult {
+            path: PathBuf::from("a.txt"),
+            duration: Duration::from_micros(1500),
+            outcome: Ok(vec![("A".to_owned(), "1".to_owned())]),
+        };
+        let err = BatchResult {
+            path: PathBuf::from("b\"c.txt"),
+            duration: Duration::from_micros(20),
+            outcome: Err("Bad\nmap".to_owned()),
+        };
+
+        assert_eq!(
+            ok.to_json(),
+            r#"{"path":"a.txt","status":"ok","duration_ms":1.500,"exits":[{"entry":"A","exit":"1"}]}"#
+        );
+        assert_eq!(
+            err.to_json(),
+            r#"{"path":"b\"c.txt","status":"error","duration_ms":0.020,"error":"Bad\nmap"}"#
+        );
+    }
+}
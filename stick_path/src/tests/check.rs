@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::check::{check_file, compare, expected_path, parse_expected, Mismatch};
+    use crate::map::Map;
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(entry, exit)| (entry.to_string(), exit.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn expected_file_is_next_to_the_map() {
+        assert_eq!(
+            expected_path(Path::new("maps/foo.txt")),
+            Path::new("maps/foo.expected")
+        );
+    }
+
+    #[test]
+    fn expected_lines_are_split_after_the_longest_entry() {
+        let map = "6 3\nA AB B\n| | |\n1 2 3".parse::<Map>().unwrap();
+
+        assert_eq!(
+            parse_expected("A1\nAB22\n\nB3\n", &map),
+            Ok(pairs(&[("A", "1"), ("AB", "22"), ("B", "3")]))
+        );
+        assert!(parse_expected("C1", &map).is_err());
+    }
+
+    #[test]
+    fn mismatches_are_reported_per_entry() {
+        let expected = pairs(&[("A", "1"), ("B", "2"), ("C", "3")]);
+        let actual = pairs(&[("A", "1"), ("B", "3"), ("D", "2")]);
+
+        assert_eq!(compare(&expected, &expected), vec![]);
+        assert_eq!(
+            compare(&expected, &actual),
+            vec![
+                Mismatch {
+                    entry: "B".to_owned(),
+                    expected: Some("2".to_owned()),
+                    actual: Some("3".to_owned())
+                },
+                Mismatch {
+                    entry: "C".to_owned(),
+                    expected: Some("3".to_owned()),
+                    actual: None
+                },
+                Mismatch {
+                    entry: "D".to_owned(),
+                    expected: None,
+                    actual: Some("2".to_owned())
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn repository_maps_match_their_expected_files() {
+        let maps = Path::new(env!("CARGO_MANIFEST_DIR")).join("maps");
+
+        for name in ["map_7_7.txt", "map_16_18.txt", "huge_map.txt"] {
+            assert_eq!(check_file(&maps.join(name), false), Ok(vec![]));
+        }
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use crate::generator::{self, Rng};
+    use crate::map::Map;
+
+    #[test]
+    fn commuting_rungs_share_a_level() {
+        let map = "9 6\nA B C D E\n|--| | | |\n| | | | |\n| | |--| |\n| | | |--|\n1 2 3 4 5"
+            .parse::<Map>()
+            .unwrap();
+        let compacted = map.compact().unwrap();
+
+        assert_eq!(compacted.height, 2);
+        assert_eq!(
+            compacted.to_string(),
+            "11 4\nA B C D E\n|--| |--| |\n| | | |--|\n1 2 3 4 5\n"
+        );
+        assert_eq!(compacted.solve(), map.solve());
+    }
+
+    #[test]
+    fn weights_and_wrap_rungs_are_kept() {
+        let mut map = "9 5\nA B C\n| | |\n|-+2-| |\n-7-| | |-\n1 2 3"
+            .parse::<Map>()
+            .unwrap();
+
+        map.circular = true;
+
+        let compacted = map.compact().unwrap();
+
+        assert_eq!(compacted.height, 2);
+        assert_eq!(compacted.content[0], "|-+2-||".chars().collect::<Vec<_>>());
+        assert_eq!(compacted.rung_weight(2, 1), Ok(7));
+        assert_eq!(compacted.solve(), map.solve());
+        assert!(compacted.validate().is_ok());
+    }
+
+    #[test]
+    fn obstacles_are_errors() {
+        let map = "5 4\nA B C\n|--| |\nx | |\n1 2 3".parse::<Map>().unwrap();
+
+        assert!(map.compact().is_err());
+    }
+
+    #[test]
+    fn compaction_keeps_the_exits_of_generated_maps() {
+        let mut rng = Rng::new(3);
+
+        for circular in [false, true] {
+            for _ in 0..20 {
+                let map = generator::generate(7, 30, 0.3, circular, &mut rng);
+                let compacted = map.compact().unwrap();
+
+                assert!(compacted.height <= map.height);
+                assert!(compacted.validate().is_ok());
+                assert_eq!(compacted.solve(), map.solve());
+                assert_eq!(compacted.compact().unwrap().height, compacted.height);
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use crate::cost::{costs, summary, CostSummary};
+    use crate::lint::lint;
+    use crate::map::{Map, Outcome};
+
+    fn weighted_map() -> Map {
+        "9 4\nA B C\n|-3-| |\n| |-+2-|\n1 2 3"
+            .parse::<Map>()
+            .unwrap()
+    }
+
+    mod weights {
+        use super::*;
+
+        #[test]
+        fn weights_are_read_inside_rungs() {
+            let weight =
+                |segment: &str| Map::weight_in_segment(&segment.chars().collect::<Vec<_>>());
+
+            assert_eq!(weight("-3-"), Some(3));
+            assert_eq!(weight("--12--"), Some(12));
+            assert_eq!(weight("-+2-"), Some(-2));
+            assert_eq!(weight("--"), None);
+            assert_eq!(weight("3-"), None);
+            assert_eq!(weight("-3a-"), None);
+            assert_eq!(weight("-+-"), None);
+        }
+
+        #[test]
+        fn rungs_without_weight_cost_nothing() {
+            let map = weighted_map();
+
+            assert_eq!(map.rung_weight(0, 0), Ok(3));
+            assert_eq!(map.rung_weight(1, 1), Ok(-2));
+            assert_eq!(map.rung_weight(1, 0), Ok(0));
+            assert!(map.validate().is_ok());
+            assert!(lint(&map, &[]).is_empty());
+        }
+
+        #[test]
+        fn wrap_rungs_can_be_weighted() {
+            let mut map = "7 3\nA B C\n-5-| | |-\n1 2 3".parse::<Map>().unwrap();
+
+            map.circular = true;
+            assert_eq!(map.cost_for_entry("A"), Ok(5));
+            assert_eq!(map.cost_for_entry("C"), Ok(5));
+            assert_eq!(map.cost_for_entry("B"), Ok(0));
+        }
+
+        #[test]
+        fn removing_a_weighted_rung_removes_its_weight() {
+            let mut map = weighted_map();
+
+            map.remove_rung(0, 0).unwrap();
+            assert_eq!(map.content[0], vec!['|', '|', '|']);
+            map.undo().unwrap();
+            assert_eq!(map.rung_weight(0, 0), Ok(3));
+        }
+    }
+
+    mod costs {
+        use super::*;
+
+        #[test]
+        fn costs_sum_the_weights_of_the_rungs_taken() {
+            let costs = costs(&weighted_map()).unwrap();
+
+            assert_eq!(
+                costs
+                    .iter()
+                    .map(|cost| (cost.entry.as_str(), cost.cost))
+                    .collect::<Vec<_>>(),
+                vec![("A", 1), ("B", 3), ("C", -2)]
+            );
+            assert_eq!(costs[0].outcome, Outcome::Exit("3".to_owned()));
+        }
+
+        #[test]
+        fn summary_skips_trapped_entries() {
+            let map = "9 4\nA B C\n|-3-| |\n| x |\n1 2 3".parse::<Map>().unwrap();
+            let costs = costs(&map).unwrap();
+
+            assert_eq!(
+                summary(&costs),
+                Some(CostSummary {
+                    cheapest: vec!["C".to_owned()],
+                    cheapest_cost: 0,
+                    most_expensive: vec!["B".to_owned()],
+                    most_expensive_cost: 3,
+                })
+            );
+            assert_eq!(
+                summary(&costs[..1]).map(|summary| summary.to_string()),
+                None
+            );
+        }
+    }
+}
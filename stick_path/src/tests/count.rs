@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::count::{count, ladders, rows};
+    use crate::generator::{entry_label, exit_label};
+
+    fn labels(columns: usize) -> (Vec<String>, Vec<String>) {
+        (
+            (0..columns).map(entry_label).collect(),
+            (0..columns).map(exit_label).collect(),
+        )
+    }
+
+    mod rows {
+        use super::*;
+
+        #[test]
+        fn rows_have_no_conflicting_rungs() {
+            // Fibonacci numbers for straight ladders, Lucas numbers for circular ones
+            assert_eq!(rows(4, false).len(), 5);
+            assert_eq!(rows(5, false).len(), 8);
+            assert_eq!(rows(4, true).len(), 7);
+            assert_eq!(rows(5, true).len(), 11);
+        }
+    }
+
+    mod count {
+        use super::*;
+
+        #[test]
+        fn small_ladders_are_counted() {
+            // One line of 3 columns: empty, left rung, right rung
+            assert_eq!(count(&[0, 1, 2], 1, false), Ok(1));
+            assert_eq!(count(&[1, 0, 2], 1, false), Ok(1));
+            assert_eq!(count(&[2, 0, 1], 1, false), Ok(0));
+            assert_eq!(count(&[2, 0, 1], 2, false), Ok(1));
+            // Every line of 3 columns leads somewhere: 3^3 ladders of height 3 in total
+            let total = [
+                [0, 1, 2],
+                [0, 2, 1],
+                [1, 0, 2],
+                [1, 2, 0],
+                [2, 0, 1],
+                [2, 1, 0],
+            ]
+            .iter()
+            .map(|permutation| count(permutation, 3, false).unwrap())
+            .sum::<u128>();
+
+            assert_eq!(total, 27);
+        }
+
+        #[test]
+        fn invalid_permutations_are_errors() {
+            assert!(count(&[0, 0, 1], 2, false).is_err());
+            assert!(count(&[0, 1, 3], 2, false).is_err());
+            assert!(count(&(0..10).collect::<Vec<_>>(), 2, false).is_err());
+        }
+
+        #[test]
+        fn large_counts_do_not_overflow_for_reasonable_sizes() {
+            let total = count(&[0, 1, 2, 3, 4], 30, false).unwrap();
+
+            assert!(total > 0);
+        }
+    }
+
+    mod ladders {
+        use super::*;
+
+        #[test]
+        fn ladders_realise_the_permutation_and_match_the_count() {
+            for circular in [false, true] {
+                let permutation = [2, 0, 3, 1];
+                let (entries, exits) = labels(4);
+                let maps = ladders(entries, exits, &permutation, 4, circular)
+                    .unwrap()
+                    .collect::<Vec<_>>();
+                let distinct = maps
+                    .iter()
+                    .map(|map| map.to_string())
+                    .collect::<HashSet<_>>();
+
+                assert_eq!(
+                    maps.len() as u128,
+                    count(&permutation, 4, circular).unwrap()
+                );
+                assert_eq!(distinct.len(), maps.len());
+                for map in &maps {
+                    let mut map = map.clone();
+
+                    assert!(map.validate().is_ok());
+                    assert_eq!(map.height, 4);
+                    assert_eq!(map.permutation(), Ok(&permutation[..]));
+                }
+            }
+        }
+
+        #[test]
+        fn ladders_are_listed_lazily() {
+            let (entries, exits) = labels(8);
+            let permutation = [7, 6, 5, 4, 3, 2, 1, 0];
+            let first = ladders(entries, exits, &permutation, 40, false)
+                .unwrap()
+                .take(3)
+                .collect::<Vec<_>>();
+
+            assert_eq!(first.len(), 3);
+        }
+
+        #[test]
+        fn unreachable_permutations_give_no_ladder() {
+            let (entries, exits) = labels(3);
+
+            assert_eq!(
+                ladders(entries, exits, &[2, 1, 0], 2, false)
+                    .unwrap()
+                    .count(),
+                0
+            );
+        }
+    }
+}
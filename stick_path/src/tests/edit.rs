@@ -0,0 +1,302 @@
+#[cfg(test)]
+mod tests {
+    use crate::edit::Edit;
+    use crate::generator::{self, Rng};
+    use crate::map::Map;
+
+    fn fresh_permutation(map: &Map) -> Vec<usize> {
+        (0..map.entries.len())
+            .map(|x| (0..map.height).fold(x, |x, y| map.next_column_for_coords(x, y).unwrap()))
+            .collect()
+    }
+
+    /// Content, entries, exits and cached permutation of a map.
+    type State = (Vec<Vec<char>>, Vec<String>, Vec<String>, Option<Vec<usize>>);
+
+    /// The parts of a map an edit changes, to check that a rejected edit left them untouched.
+    fn state(map: &Map) -> State {
+        (
+            map.content.clone(),
+            map.entries.clone(),
+            map.exits.clone(),
+            map.permutation.clone(),
+        )
+    }
+
+    fn simple_map() -> Map {
+        "7 5\nA B C D\n|-| | |\n| |-| |\n|-| |-|\n1 2 3 4"
+            .parse::<Map>()
+            .unwrap()
+    }
+
+    mod rungs {
+        use super::*;
+
+        #[test]
+        fn rungs_can_be_added_removed_and_toggled() {
+            let mut map = simple_map();
+
+            map.remove_rung(0, 0).unwrap();
+            map.add_rung(0, 2).unwrap();
+            map.toggle_rung(1, 1).unwrap();
+            assert_eq!(map.rungs_for_line(0), Ok(vec![false, false, true, false]));
+            assert_eq!(map.rungs_for_line(1), Ok(vec![false; 4]));
+            assert!(map.validate().is_ok());
+        }
+
+        #[test]
+        fn rungs_are_written_like_map_files() {
+            let mut map = "5 3\nA B C\n| | |\n1 2 3".parse::<Map>().unwrap();
+
+            map.add_rung(0, 0).unwrap();
+            assert_eq!(map.to_string(), "6 3\nA B C\n|--| |\n1 2 3\n");
+            map.toggle_rung(0, 0).unwrap();
+            map.add_rung(0, 1).unwrap();
+            assert_eq!(map.text_lines(), ["| |--|"]);
+        }
+
+        #[test]
+        fn conflicting_rungs_are_rejected() {
+            let mut map = simple_map();
+
+            assert!(map.add_rung(0, 1).is_err());
+            assert!(map.add_rung(1, 0).is_err());
+            assert!(map.add_rung(0, 0).is_err());
+            assert!(map.remove_rung(0, 2).is_err());
+            assert!(map.add_rung(0, 3).is_err());
+            assert!(map.add_rung(5, 0).is_err());
+            assert_eq!(map.to_string(), simple_map().to_string());
+        }
+
+        #[test]
+        fn wrap_rungs_can_only_be_added_on_circular_maps() {
+            let mut map = "7 3\nA B C D\n| |-| |\n1 2 3 4".parse::<Map>().unwrap();
+
+            assert!(map.add_rung(0, 3).is_err());
+            map.circular = true;
+            map.add_rung(0, 3).unwrap();
+            assert_eq!(map.content[0], "-||-||-".chars().collect::<Vec<_>>());
+            assert_eq!(map.permutation(), Ok(&[3, 2, 1, 0][..]));
+        }
+    }
+
+    mod lines_and_columns {
+        use super::*;
+
+        #[test]
+        fn lines_can_be_inserted_deleted_and_moved() {
+            let mut map = simple_map();
+
+            map.insert_line(1).unwrap();
+            assert_eq!(map.height, 4);
+            assert_eq!(map.rungs_for_line(1), Ok(vec![false; 4]));
+            map.delete_line(0).unwrap();
+            map.move_line(2, 0).unwrap();
+            assert_eq!(
+                map.to_string(),
+                "7 5\nA B C D\n|-| |-|\n| | | |\n| |-| |\n1 2 3 4\n"
+            );
+        }
+
+        #[test]
+        fn columns_can_be_inserted_and_deleted() {
+            let mut map = simple_map();
+
+            map.insert_column(1, "E", "5").unwrap();
+            assert_eq!(
+                map.to_string(),
+                "9 5\nA E B C D\n| | | | |\n| | |-| |\n| | | |-|\n1 5 2 3 4\n"
+            );
+            map.delete_column(3).unwrap();
+            assert_eq!(map.entries, vec!["A", "E", "B", "D"]);
+            assert_eq!(map.exits, vec!["1", "5", "2", "4"]);
+            assert!(map.validate().is_ok());
+        }
+
+        #[test]
+        fn columns_can_be_moved_with_their_pole() {
+            let mut map = "7 4\nA B C D\n|-| | |\n| x |-|\n1 2 3 4"
+                .parse::<Map>()
+                .unwrap();
+            let original = map.to_string();
+
+            map.move_column(1, 3).unwrap();
+            assert_eq!(map.to_string(), "7 4\nA C D B\n| | | |\n| |-| x\n1 3 4 2\n");
+            map.move_column(3, 0).unwrap();
+            assert_eq!(map.entries, vec!["B", "A", "C", "D"]);
+            assert_eq!(map.exits, vec!["2", "1", "3", "4"]);
+            assert!(map.move_column(0, 4).is_err());
+            while map.undo().unwrap() {}
+            assert_eq!(map.to_string(), original);
+        }
+
+        #[test]
+        fn width_follows_the_content() {
+            let mut map = "9 4\nA B C D\n|--| | |\n| | | |\n1 2 3 4"
+                .parse::<Map>()
+                .unwrap();
+
+            map.move_column(0, 3).unwrap();
+            assert_eq!(map.width, 7);
+            map.undo().unwrap();
+            assert_eq!(map.width, 8);
+            map.edit(Edit::ReplaceLine {
+                at: 1,
+                content: "|--| |--|".chars().collect(),
+            })
+            .unwrap();
+            assert_eq!(map.width, 9);
+            map.undo().unwrap();
+            assert_eq!(map.width, 8);
+        }
+
+        #[test]
+        fn moving_columns_removes_the_rungs_in_the_way() {
+            let mut map = simple_map();
+
+            map.move_column(0, 2).unwrap();
+            assert_eq!(map.entries, vec!["B", "C", "A", "D"]);
+            for line in 0..map.height {
+                assert_eq!(map.rungs_for_line(line).unwrap()[1..3], [false, false]);
+            }
+            assert!(map.validate().is_ok());
+            let expected = fresh_permutation(&map);
+
+            assert_eq!(map.permutation().unwrap(), expected);
+        }
+    }
+
+    mod failures {
+        use super::*;
+
+        #[test]
+        fn failed_batches_leave_the_map_unchanged() {
+            let mut map = simple_map();
+
+            map.permutation().unwrap();
+
+            let before = state(&map);
+            let batch = Edit::Batch(vec![
+                Edit::SetRung {
+                    line: 1,
+                    column: 1,
+                    present: false,
+                },
+                Edit::InsertColumn {
+                    at: 1,
+                    entry: "E".to_owned(),
+                    exit: "5".to_owned(),
+                },
+                Edit::MoveColumn { from: 0, to: 3 },
+                Edit::DeleteColumn { at: 9 },
+            ]);
+
+            assert!(map.edit(batch).is_err());
+            assert_eq!(state(&map), before);
+            assert_eq!(map.undo(), Ok(false));
+        }
+
+        #[test]
+        fn failed_line_replacements_leave_the_map_unchanged() {
+            let mut map = simple_map();
+
+            map.permutation().unwrap();
+
+            let before = state(&map);
+
+            // The first rung is added, the second one conflicts with it
+            let content = "|-|-| |".chars().collect();
+
+            assert!(map.edit(Edit::ReplaceLine { at: 1, content }).is_err());
+            assert_eq!(state(&map), before);
+
+            let content = "|-|-| |".chars().collect();
+
+            assert!(map.edit(Edit::InsertLine { at: 2, content }).is_err());
+            assert_eq!(state(&map), before);
+            assert_eq!(map.height, 3);
+        }
+    }
+
+    mod history {
+        use super::*;
+
+        #[test]
+        fn undo_and_redo_restore_the_map() {
+            let mut map = simple_map();
+            let original = map.to_string();
+
+            map.remove_rung(0, 0).unwrap();
+            map.insert_column(2, "E", "5").unwrap();
+            map.delete_line(1).unwrap();
+            map.delete_column(0).unwrap();
+
+            let edited = map.to_string();
+
+            while map.undo().unwrap() {}
+            assert_eq!(map.to_string(), original);
+            while map.redo().unwrap() {}
+            assert_eq!(map.to_string(), edited);
+            assert_eq!(map.redo(), Ok(false));
+        }
+
+        #[test]
+        fn new_edits_clear_the_redo_history() {
+            let mut map = simple_map();
+
+            map.remove_rung(0, 0).unwrap();
+            map.undo().unwrap();
+            map.insert_line(0).unwrap();
+            assert_eq!(map.redo(), Ok(false));
+        }
+    }
+
+    mod permutation {
+        use super::*;
+
+        #[test]
+        fn permutation_follows_the_exits() {
+            let mut map = simple_map();
+
+            let expected = fresh_permutation(&map);
+
+            assert_eq!(map.permutation().unwrap(), expected);
+        }
+
+        #[test]
+        fn permutation_stays_up_to_date_with_random_edits() {
+            for circular in [false, true] {
+                let mut rng = Rng::new(7);
+                let mut map = generator::generate(6, 20, 0.4, circular, &mut rng);
+
+                map.permutation().unwrap();
+                for _ in 0..300 {
+                    let columns = map.entries.len();
+                    let line = (rng.next_u64() as usize) % map.height.max(1);
+                    let column = (rng.next_u64() as usize) % columns;
+
+                    // Edits can be rejected, the map must then be left as it was
+                    let before = state(&map);
+                    let result = match rng.next_u64() % 9 {
+                        0 if map.height > 2 => map.delete_line(line),
+                        1 => map.insert_line(line),
+                        2 => map.move_line(line, (rng.next_u64() as usize) % map.height),
+                        3 if columns < 8 => map.insert_column(column, "X", "0"),
+                        4 if columns > 3 => map.delete_column(column),
+                        5 => map.undo().map(|_| ()),
+                        6 => map.redo().map(|_| ()),
+                        7 => map.move_column(column, (rng.next_u64() as usize) % columns),
+                        _ => map.toggle_rung(line, column),
+                    };
+                    if result.is_err() {
+                        assert_eq!(state(&map), before);
+                    }
+                    assert!(map.validate().is_ok());
+                    let expected = fresh_permutation(&map);
+
+                    assert_eq!(map.permutation().unwrap(), expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use crate::error::{CliError, ErrorFormat, ErrorKind};
+    use crate::stream::MapReader;
+
+    #[test]
+    fn kinds_have_distinct_exit_codes() {
+        let kinds = [
+            ErrorKind::Usage,
+            ErrorKind::Io,
+            ErrorKind::Parse,
+            ErrorKind::Validation,
+            ErrorKind::Solve,
+        ];
+        let mut codes = kinds
+            .iter()
+            .map(|kind| kind.exit_code())
+            .collect::<Vec<_>>();
+
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), kinds.len());
+        assert!(codes.iter().all(|code| *code > 1));
+    }
+
+    #[test]
+    fn errors_are_formatted_as_text() {
+        let err = CliError::validation("Conflicting rungs on line 2").for_map(3);
+
+        assert_eq!(err.to_string(), "Map 3: Conflicting rungs on line 2");
+        assert_eq!(
+            CliError::usage("Unknown command foo").render(ErrorFormat::Text),
+            "Unknown command foo"
+        );
+    }
+
+    #[test]
+    fn errors_are_formatted_as_json() {
+        assert_eq!(
+            CliError::parse("Invalid \"size\"").render(ErrorFormat::Json),
+            r#"{"kind":"parse","exit_code":4,"message":"Invalid \"size\""}"#
+        );
+        assert_eq!(
+            CliError::solve("Unknown entry Z").for_map(2).to_json(),
+            r#"{"kind":"solve","exit_code":6,"map":2,"message":"Unknown entry Z"}"#
+        );
+    }
+
+    #[test]
+    fn error_formats_are_parsed() {
+        assert_eq!("text".parse(), Ok(ErrorFormat::Text));
+        assert_eq!("json".parse(), Ok(ErrorFormat::Json));
+        assert!("xml".parse::<ErrorFormat>().is_err());
+    }
+
+    #[test]
+    fn read_errors_keep_their_kind() {
+        let err = MapReader::new("1 3\nA B\n| |\n1 2\n".as_bytes())
+            .next()
+            .unwrap()
+            .unwrap_err();
+
+        assert_eq!(CliError::from(err).kind, ErrorKind::Parse);
+    }
+}
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use crate::fairness::{
+        analyse, chi_square_critical_value, recommended_height, rung_probabilities,
+        MIXING_TOLERANCE,
+    };
+    use crate::generator::{random_rungs, Rng};
+
+    #[test]
+    fn rung_probabilities_match_generated_rungs() {
+        let mut rng = Rng::new(11);
+
+        for circular in [false, true] {
+            let probabilities = rung_probabilities(5, 0.6, circular);
+            let mut counts = [0; 5];
+
+            for _ in 0..20_000 {
+                for (slot, rung) in random_rungs(5, 0.6, circular, &mut rng).iter().enumerate() {
+                    counts[slot] += *rung as usize;
+                }
+            }
+            for slot in 0..5 {
+                assert!((counts[slot] as f64 / 20_000.0 - probabilities[slot]).abs() < 0.02);
+            }
+        }
+    }
+
+    #[test]
+    fn critical_value_is_close_to_tables() {
+        assert!((chi_square_critical_value(1) - 3.84).abs() < 0.1);
+        assert!((chi_square_critical_value(10) - 18.31).abs() < 0.1);
+        assert!((chi_square_critical_value(30) - 43.77).abs() < 0.1);
+    }
+
+    #[test]
+    fn recommended_height_grows_with_width() {
+        let small = recommended_height(3, 0.5, false).expect("Could not find height");
+        let large = recommended_height(8, 0.5, false).expect("Could not find height");
+
+        assert!(small < large);
+        assert_eq!(recommended_height(4, 0.0, false), None);
+    }
+
+    /// Walks the distribution of every entry down line after line until it is close to uniform.
+    fn linear_recommended_height(columns: usize, density: f64, circular: bool) -> usize {
+        let probabilities = rung_probabilities(columns, density, circular);
+        let uniform = 1.0 / columns as f64;
+        let mut distribution = (0..columns)
+            .map(|entry| (0..columns).map(|x| (x == entry) as usize as f64).collect())
+            .collect::<Vec<Vec<f64>>>();
+
+        for height in 0.. {
+            let distance = distribution
+                .iter()
+                .map(|line| line.iter().map(|p| (p - uniform).abs()).sum::<f64>() / 2.0)
+                .fold(0.0, f64::max);
+
+            if distance <= MIXING_TOLERANCE {
+                return height;
+            }
+            for line in distribution.iter_mut() {
+                let mut next = vec![0.0; columns];
+
+                for x in 0..columns {
+                    let right = probabilities[x];
+                    let left = probabilities[(x + columns - 1) % columns];
+
+                    next[x] += line[x] * (1.0 - left - right);
+                    next[(x + 1) % columns] += line[x] * right;
+                    next[(x + columns - 1) % columns] += line[x] * left;
+                }
+                *line = next;
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn recommended_height_matches_a_line_by_line_search() {
+        for (columns, density, circular) in [
+            (2, 0.5, false),
+            (3, 0.5, false),
+            (5, 0.3, true),
+            (8, 0.5, false),
+            (12, 0.8, true),
+        ] {
+            assert_eq!(
+                recommended_height(columns, density, circular),
+                Some(linear_recommended_height(columns, density, circular))
+            );
+        }
+    }
+
+    #[test]
+    fn ladders_that_never_mix_are_not_reachable() {
+        // Every line holds the same rungs, walkers only go back and forth
+        assert_eq!(recommended_height(6, 1.0, false), None);
+        assert_eq!(recommended_height(300, 0.5, false), None);
+        assert_eq!(recommended_height(1, 0.5, false), None);
+    }
+
+    #[test]
+    fn degrees_of_freedom_follow_one_distribution_per_entry() {
+        let report = analyse(4, 20, 0.5, false, 100, &mut Rng::new(5)).expect("Could not analyse");
+
+        assert_eq!(report.degrees_of_freedom, 12);
+    }
+
+    #[test]
+    fn short_ladders_are_not_fair() {
+        let report = analyse(6, 3, 0.5, false, 5_000, &mut Rng::new(5)).expect("Could not analyse");
+
+        assert!(!report.looks_uniform());
+        assert_eq!(report.counts.iter().flatten().sum::<usize>(), 6 * 5_000);
+    }
+
+    #[test]
+    fn recommended_height_is_fair() {
+        let height = recommended_height(4, 0.5, false).expect("Could not find height");
+        let report =
+            analyse(4, height, 0.5, false, 5_000, &mut Rng::new(5)).expect("Could not analyse");
+
+        assert!(report.looks_uniform());
+        assert!(report.max_deviation < 0.03);
+    }
+
+    #[test]
+    fn invalid_parameters_are_errors() {
+        assert!(analyse(1, 10, 0.5, false, 10, &mut Rng::new(1)).is_err());
+        assert!(analyse(4, 10, 1.5, false, 10, &mut Rng::new(1)).is_err());
+        assert!(analyse(4, 10, 0.5, false, 0, &mut Rng::new(1)).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use crate::check::parse_expected;
+    use crate::fill::fill;
+    use crate::map::Map;
+
+    const MAP: &str = include_str!("../../maps/map_16_18.txt");
+    const EXPECTED: &str = include_str!("../../maps/map_16_18.expected");
+
+    fn expected(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(entry, exit)| (entry.to_string(), exit.to_string()))
+            .collect()
+    }
+
+    mod unknowns {
+        use super::*;
+
+        #[test]
+        fn unknown_rungs_are_read_per_column() {
+            let map = "5 3\nA B C\n|??| |\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(map.has_unknowns());
+            assert_eq!(map.unknown_rungs_for_line(0).unwrap(), [true, false, false]);
+            assert_eq!(map.rungs_for_line(0).unwrap(), [false, false, false]);
+        }
+
+        #[test]
+        fn maps_with_unknown_rungs_are_not_valid() {
+            let map = "5 3\nA B C\n|??| |\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(map.validate().unwrap_err().contains("unknown rungs"));
+        }
+
+        #[test]
+        fn unknown_rungs_must_be_whole_segments() {
+            let map = "5 3\nA B C\n|?-| |\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(map.unknown_rungs_for_line(0).is_err());
+        }
+
+        #[test]
+        fn unknown_wrap_rungs_need_both_edges_of_a_circular_map() {
+            let mut map = "7 3\nA B C\n?| | |?\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(map.unknown_rungs_for_line(0).is_err());
+            map.circular = true;
+            assert_eq!(map.unknown_rungs_for_line(0).unwrap(), [false, false, true]);
+
+            let map = "7 3\nA B C\n?| | |\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(map.unknown_rungs_for_line(0).is_err());
+        }
+
+        #[test]
+        fn filling_replaces_the_unknown_rungs_only() {
+            let mut map = "7 4\nA B C\n?|??|-3-|?\n|??| |\n1 2 3"
+                .parse::<Map>()
+                .unwrap();
+
+            map.circular = true;
+
+            let filled = map
+                .fill_unknowns(&[vec![false, false, true], vec![true, false, false]])
+                .unwrap();
+
+            assert_eq!(filled.text_lines(), ["-| |-3-|-", "|--| |"]);
+        }
+    }
+
+    mod fill {
+        use super::*;
+
+        #[test]
+        fn every_completion_is_found() {
+            let map = "5 4\nA B C\n|??|??|\n|??|??|\n1 2 3"
+                .parse::<Map>()
+                .unwrap();
+            let completions = fill(&map, &expected(&[("A", "3"), ("B", "1"), ("C", "2")])).unwrap();
+
+            assert_eq!(completions.len(), 1);
+            assert_eq!(completions[0].text_lines(), ["|--| |", "| |--|"]);
+        }
+
+        #[test]
+        fn entries_left_out_of_the_mapping_go_anywhere() {
+            let map = "5 4\nA B C\n|??|??|\n|??|??|\n1 2 3"
+                .parse::<Map>()
+                .unwrap();
+            let completions = fill(&map, &expected(&[("A", "1")])).unwrap();
+
+            assert_eq!(completions.len(), 5);
+            for completion in completions {
+                assert_eq!(completion.exit_for_entry("A").unwrap(), "1");
+            }
+        }
+
+        #[test]
+        fn impossible_mappings_have_no_completion() {
+            let map = "5 3\nA B C\n|??|??|\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(fill(&map, &expected(&[("A", "3")])).unwrap().is_empty());
+        }
+
+        #[test]
+        fn obstacles_trap_completions() {
+            let map = "5 4\nA B C\n|??|??|\nx | |\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(fill(&map, &expected(&[("B", "1")])).unwrap().is_empty());
+            assert_eq!(fill(&map, &expected(&[("A", "2")])).unwrap().len(), 1);
+        }
+
+        #[test]
+        fn known_rungs_must_be_valid() {
+            let map = "5 3\nA B C\n|--|--|\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(fill(&map, &[]).is_err());
+        }
+
+        #[test]
+        fn maps_with_portals_can_not_be_filled() {
+            let map = "A B\n[a -> a]\no |\n1 2".parse::<Map>().unwrap();
+
+            assert!(fill(&map, &[]).is_err());
+        }
+
+        #[test]
+        fn puzzles_the_size_of_the_sample_maps_are_solved() {
+            let map = MAP.parse::<Map>().unwrap();
+            // Every rung is hidden: 36 unknown rungs
+            let puzzle = MAP.replace("--", "??").parse::<Map>().unwrap();
+            let expected = parse_expected(EXPECTED, &map).unwrap();
+            let completions = fill(&puzzle, &expected).unwrap();
+
+            assert!(completions
+                .iter()
+                .any(|completion| completion.content == map.content));
+            for completion in completions {
+                assert_eq!(completion.solve().unwrap(), expected);
+            }
+        }
+    }
+}
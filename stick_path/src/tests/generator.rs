@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use crate::generator::{entry_label, exit_label, generate, random_rungs, Rng};
+
+    #[test]
+    fn same_seed_gives_same_numbers() {
+        let mut first = Rng::new(42);
+        let mut second = Rng::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+        assert!((0..1000).all(|_| (0.0..1.0).contains(&first.next_f64())));
+    }
+
+    #[test]
+    fn labels_follow_spreadsheet_order() {
+        assert_eq!(entry_label(0), "A");
+        assert_eq!(entry_label(25), "Z");
+        assert_eq!(entry_label(26), "AA");
+        assert_eq!(entry_label(27), "AB");
+        assert_eq!(entry_label(702), "AAA");
+        assert_eq!(exit_label(0), "1");
+        assert_eq!(exit_label(9), "10");
+    }
+
+    #[test]
+    fn random_rungs_never_conflict() {
+        let mut rng = Rng::new(7);
+
+        for circular in [false, true] {
+            for _ in 0..1000 {
+                let rungs = random_rungs(6, 0.9, circular, &mut rng);
+
+                for slot in 0..6 {
+                    assert!(!(rungs[slot] && rungs[(slot + 1) % 6]));
+                }
+                assert!(circular || !rungs[5]);
+            }
+        }
+    }
+
+    #[test]
+    fn single_columns_never_wrap() {
+        let mut rng = Rng::new(26);
+
+        for _ in 0..100 {
+            assert_eq!(random_rungs(1, 1.0, true, &mut rng), [false]);
+        }
+
+        let map = generate(1, 10, 1.0, true, &mut rng);
+
+        assert!(map.validate().is_ok());
+    }
+
+    #[test]
+    fn generated_maps_are_valid() {
+        let mut rng = Rng::new(3);
+
+        for circular in [false, true] {
+            let map = generate(8, 30, 0.5, circular, &mut rng);
+
+            assert_eq!(map.height, 30);
+            assert_eq!(map.entries.len(), 8);
+            assert!(map.validate().is_ok());
+            assert_eq!(map.to_string().lines().count(), 33);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use crate::layer::Layer;
+    use crate::map::{Map, Outcome};
+    use crate::schema::MapSchema;
+
+    fn tower() -> Map {
+        "5 8\nA B C\n[ground -> top]\n|--o |\n| | |\n[top]\n| |--|\n| | |\n1 2 3"
+            .parse::<Map>()
+            .unwrap()
+    }
+
+    fn looping() -> Map {
+        "A B\n[a]\n| |\n| |\n[b -> a]\n| |\n| o\n1 2"
+            .parse::<Map>()
+            .unwrap()
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn headers_are_split_from_the_content() {
+            let map = tower();
+
+            assert_eq!(map.height, 4);
+            assert_eq!(map.content.len(), 4);
+            assert_eq!(
+                map.layers,
+                vec![
+                    Layer {
+                        name: "ground".to_owned(),
+                        target: Some("top".to_owned()),
+                        start: 0
+                    },
+                    Layer {
+                        name: "top".to_owned(),
+                        target: None,
+                        start: 2
+                    }
+                ]
+            );
+            assert_eq!(map.layer_for_line(1), Some(0));
+            assert_eq!(map.layer_for_line(3), Some(1));
+            assert!(map.validate().is_ok());
+        }
+
+        #[test]
+        fn headers_are_written_back() {
+            let text = "5 8\nA B C\n[ground -> top]\n|--o |\n| | |\n[top]\n| |--|\n| | |\n1 2 3\n";
+
+            assert_eq!(tower().to_string(), text);
+            assert_eq!(
+                Map::try_from(MapSchema::from(tower())).unwrap().to_string(),
+                text
+            );
+            assert_eq!(looping().text_height(), 8);
+        }
+
+        #[test]
+        fn invalid_headers_are_errors() {
+            assert!("A B\n[]\n| |\n1 2".parse::<Map>().is_err());
+            assert!("A B\n[a ->]\n| |\n1 2".parse::<Map>().is_err());
+            assert!("A B\n[a]\n| |\n[a]\n| |\n1 2".parse::<Map>().is_err());
+        }
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test]
+        fn targets_must_exist() {
+            let map = "A B\n[a -> b]\n| o\n1 2".parse::<Map>().unwrap();
+
+            assert!(map.validate().is_err());
+        }
+
+        #[test]
+        fn portals_need_a_target() {
+            let without_target = "A B\n[a]\n| o\n1 2".parse::<Map>().unwrap();
+            let without_layer = "A B\n| o\n1 2".parse::<Map>().unwrap();
+
+            assert!(without_target.validate().is_err());
+            assert!(without_layer.validate().is_err());
+        }
+    }
+
+    mod walk {
+        use super::*;
+
+        #[test]
+        fn portals_lead_to_the_line_below_in_the_target_layer() {
+            let map = tower();
+
+            // A takes the portal on the first line and skips the rung of the top layer
+            assert_eq!(
+                map.walk_for_entry("A").unwrap(),
+                (vec![(0, 1), (3, 1)], Outcome::Exit("2".to_owned()))
+            );
+            assert_eq!(map.trace_for_entry("A").unwrap(), vec![0, 1, 1]);
+            assert_eq!(map.exit_for_entry("B").unwrap(), "1");
+            assert_eq!(map.exit_for_entry("C").unwrap(), "2");
+        }
+
+        #[test]
+        fn layers_without_portals_are_walked_in_order() {
+            let map = "A B\n[a]\n|--|\n[b]\n|--|\n1 2".parse::<Map>().unwrap();
+
+            assert_eq!(map.exit_for_entry("A").unwrap(), "1");
+        }
+
+        #[test]
+        fn portal_loops_are_errors() {
+            let map = looping();
+
+            assert!(map.validate().is_ok());
+            assert_eq!(map.exit_for_entry("A").unwrap(), "1");
+            assert!(map
+                .outcome_for_entry("B")
+                .unwrap_err()
+                .contains("loops forever"));
+        }
+    }
+
+    mod commands {
+        use super::*;
+
+        #[test]
+        fn line_edits_keep_lines_in_their_layer() {
+            let text = "6 7\nA B C\n[a]\n|--| |\n| |--|\n[b]\n|--| |\n1 2 3\n";
+            let mut map = text.parse::<Map>().unwrap();
+
+            // A line inserted at the start of a layer belongs to it
+            map.insert_line(2).unwrap();
+            assert_eq!(map.layers[1].start, 2);
+            map.delete_line(0).unwrap();
+            assert_eq!(map.layers[1].start, 1);
+            // Moved lines change layer
+            map.move_line(1, 0).unwrap();
+            assert_eq!(map.layers[1].start, 2);
+            assert!(map.delete_line(2).is_err());
+            while map.undo().unwrap() {}
+            assert_eq!(map.to_string(), text);
+        }
+
+        #[test]
+        fn line_by_line_commands_reject_portals() {
+            let map = tower();
+
+            assert!(map.critical_rungs("A").is_err());
+            assert!(map.compact().is_err());
+            assert!(map.clone().permutation().is_err());
+        }
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use crate::lint::{lint, WarningCode};
+    use crate::map::Map;
+
+    fn map_with_lines(lines: Vec<&str>) -> Map {
+        let mut content = vec!["A B C".to_owned()];
+
+        content.extend(lines.iter().map(|l| l.to_string()));
+        content.push("1 2 3".to_owned());
+        Map::from_size_and_content(5, content.len(), content).expect("Could not create map")
+    }
+
+    fn codes(map: &Map, allowed: &[WarningCode]) -> Vec<(WarningCode, Option<usize>)> {
+        lint(map, allowed)
+            .iter()
+            .map(|warning| (warning.code, warning.line))
+            .collect()
+    }
+
+    #[test]
+    fn clean_map_has_no_warning() {
+        let map = map_with_lines(vec!["|--| |", "| |--|"]);
+
+        assert!(lint(&map, &[]).is_empty());
+    }
+
+    #[test]
+    fn empty_lines_are_reported() {
+        let map = map_with_lines(vec!["|--| |", "", "| |--|"]);
+
+        assert_eq!(codes(&map, &[]), vec![(WarningCode::EmptyLine, Some(2))]);
+    }
+
+    #[test]
+    fn garbage_and_column_count_are_reported() {
+        let map = map_with_lines(vec!["|--| |", "| |--|ab", "| |"]);
+
+        assert_eq!(
+            codes(&map, &[]),
+            vec![
+                (WarningCode::Garbage, Some(2)),
+                (WarningCode::ColumnCount, Some(3))
+            ]
+        );
+    }
+
+    #[test]
+    fn dangling_rungs_are_reported_unless_wrapping() {
+        let mut map = map_with_lines(vec!["-|--| |", "| |--|", "-| | |-"]);
+
+        assert_eq!(
+            codes(&map, &[WarningCode::SelfExit]),
+            vec![
+                (WarningCode::DanglingRung, Some(1)),
+                (WarningCode::DanglingRung, Some(3)),
+                (WarningCode::DanglingRung, Some(3))
+            ]
+        );
+        map.circular = true;
+        assert_eq!(
+            codes(&map, &[WarningCode::SelfExit]),
+            vec![(WarningCode::DanglingRung, Some(1))]
+        );
+    }
+
+    #[test]
+    fn cancelling_rungs_and_self_exits_are_reported() {
+        let map = map_with_lines(vec!["| |--|", "| |--|"]);
+        let warnings = lint(&map, &[]);
+
+        assert_eq!(warnings.len(), 4);
+        assert_eq!(warnings[0].code, WarningCode::CancellingRungs);
+        assert_eq!((warnings[0].line, warnings[0].column), (Some(2), Some(2)));
+        assert!(warnings[1..]
+            .iter()
+            .all(|warning| warning.code == WarningCode::SelfExit));
+    }
+
+    #[test]
+    fn trapped_entries_are_reported() {
+        let map = map_with_lines(vec!["|--| |", "| x |"]);
+        let warnings = lint(&map, &[WarningCode::SelfExit]);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::Trapped);
+        assert_eq!((warnings[0].line, warnings[0].column), (Some(2), Some(2)));
+        assert!(warnings[0].message.starts_with("Entry A"));
+    }
+
+    #[test]
+    fn last_rungs_crossed_only_by_trapped_walkers_are_reported() {
+        let map = map_with_lines(vec!["|--| |", "| x--x"]);
+        let warnings = lint(&map, &[WarningCode::Trapped]);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::UselessLastRung);
+        assert_eq!((warnings[0].line, warnings[0].column), (Some(2), Some(2)));
+
+        // The same rung matters as soon as one of its walkers gets to an exit
+        let map = map_with_lines(vec!["|--| |", "| x--|"]);
+
+        assert!(!codes(&map, &[]).contains(&(WarningCode::UselessLastRung, Some(2))));
+    }
+
+    #[test]
+    fn allowed_codes_are_suppressed() {
+        let map = map_with_lines(vec!["|--| |", "", "| |--|#"]);
+
+        assert_eq!(
+            codes(&map, &[WarningCode::EmptyLine]),
+            vec![(WarningCode::Garbage, Some(3))]
+        );
+        assert!(lint(&map, &WarningCode::ALL).is_empty());
+    }
+
+    #[test]
+    fn codes_are_parsed_back() {
+        for code in WarningCode::ALL {
+            assert_eq!(code.code().parse(), Ok(code));
+        }
+        assert!("W999".parse::<WarningCode>().is_err());
+    }
+}
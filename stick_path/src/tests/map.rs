@@ -63,6 +63,90 @@ mod tests {
         }
     }
 
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn valid_text_generates_the_map() {
+            let map = "5 5\nA  B  C\n|--|  |\n|  |  |\n|  |--|\n1  2  3\n".parse::<Map>();
+
+            assert_eq!(map.map(|map| map.content), Ok(simple_map().content));
+        }
+
+        #[test]
+        fn invalid_headers_are_errors() {
+            assert!("".parse::<Map>().is_err());
+            assert!("5".parse::<Map>().is_err());
+            assert!("5 0".parse::<Map>().is_err());
+        }
+
+        #[test]
+        fn size_lines_must_hold_the_labels() {
+            assert!("2 3\nA B\n| |\n1 2".parse::<Map>().is_err());
+            assert!("3 3\nA B\n| |\n10 20".parse::<Map>().is_err());
+            assert!("3 3\nA B\n|--|\n1 2".parse::<Map>().is_ok());
+            assert!("9 3\nA B\n| |\n1 2".parse::<Map>().is_ok());
+        }
+
+        #[test]
+        fn size_line_is_optional() {
+            let map = "A  B  C\n|--|  |\n|  |  |\n|  |--|\n1  2  3\n\n5 3\n"
+                .parse::<Map>()
+                .expect("Could not parse map");
+
+            assert!(map.inferred_size);
+            assert_eq!((map.width, map.height), (7, 3));
+            assert_eq!(map.content, simple_map().content);
+            assert!(
+                !"5 5\nA  B  C\n|--|  |\n|  |  |\n|  |--|\n1  2  3"
+                    .parse::<Map>()
+                    .unwrap()
+                    .inferred_size
+            );
+        }
+
+        #[test]
+        fn numbers_are_labels_unless_they_make_a_size_line() {
+            let labels = |text: &str| {
+                let map = text.parse::<Map>().expect("Could not parse map");
+
+                (map.inferred_size, map.entries.join(" "))
+            };
+
+            assert_eq!(labels("5 B\n| |\n1 2"), (true, "5 B".to_owned()));
+            assert_eq!(labels("1 2 3\n| | |\n3 2 1"), (true, "1 2 3".to_owned()));
+            // Followed by a line with poles, or not a positive size
+            assert_eq!(labels("1 2\n| |\n2 1"), (true, "1 2".to_owned()));
+            assert_eq!(labels("5 0\nA\n1"), (true, "5 0".to_owned()));
+            assert_eq!(labels("3 3\n1 2\n| |\n2 1"), (false, "1 2".to_owned()));
+            assert!("A B\n1 2".parse::<Map>().is_ok());
+            assert!("A B".parse::<Map>().is_err());
+        }
+
+        #[test]
+        fn truncated_maps_are_errors() {
+            assert!("7 7\nA B C\n".parse::<Map>().is_err());
+            assert!("3 4\nA B\n1 2".parse::<Map>().is_err());
+            assert!("7 4\n1 2\n|--|\n2 1".parse::<Map>().is_err());
+        }
+
+        #[test]
+        fn heights_too_small_are_errors() {
+            assert!("5 1\nA\n1".parse::<Map>().is_err());
+            assert!("5 2\nA\n1".parse::<Map>().is_ok());
+        }
+
+        #[test]
+        fn empty_lines_can_be_solved() {
+            let map = "5 4\nA B\n\n||\n1 2"
+                .parse::<Map>()
+                .expect("Could not parse map");
+
+            assert!(map.exit_for_entry("A").is_err());
+            assert_eq!(map.can_go_to_sides_for_coords(1, 1), Ok((false, false)));
+        }
+    }
+
     mod validate {
         use super::*;
 
@@ -77,6 +161,7 @@ mod tests {
                     "".chars().collect::<Vec<_>>(),
                     "".chars().collect::<Vec<_>>(),
                 ],
+                ..Map::default()
             };
 
             assert!(map.validate().is_err());
@@ -90,10 +175,40 @@ mod tests {
                 entries: vec!["A".to_owned(), "B".to_owned(), "C".to_owned()],
                 exits: vec!["1".to_owned(), "2".to_owned()],
                 content: vec!["".chars().collect::<Vec<_>>()],
+                ..Map::default()
             };
 
             assert!(map.validate().is_err());
         }
+
+        #[test]
+        fn conflicting_rungs_are_not_validated() {
+            let map = Map::from_size_and_content(
+                5,
+                5,
+                vec![
+                    "A  B  C".to_owned(),
+                    "|--|--|".to_owned(),
+                    "|  |  |".to_owned(),
+                    "|  |  |".to_owned(),
+                    "1  2  3".to_owned(),
+                ],
+            )
+            .expect("Could not create map");
+
+            assert!(map.validate().is_err());
+            assert!(simple_map().validate().is_ok());
+        }
+
+        #[test]
+        fn lines_missing_poles_are_not_validated() {
+            let map = "5 3\nA B C\n| |\n1 2 3".parse::<Map>().unwrap();
+
+            assert_eq!(
+                map.validate(),
+                Err("Line 1 has 2 poles for 3 entries".to_owned())
+            );
+        }
     }
 
     mod content_coords_for_coords {
@@ -265,4 +380,119 @@ mod tests {
             assert_eq!(map.exit_for_entry("C"), Ok("3".to_owned()));
         }
     }
+
+    mod circular {
+        use super::*;
+
+        fn circular_map(lines: Vec<&str>) -> Map {
+            let mut content = vec!["A B C".to_owned()];
+
+            content.extend(lines.iter().map(|l| l.to_string()));
+            content.push("1 2 3".to_owned());
+
+            let mut map = Map::from_size_and_content(5, content.len(), content)
+                .expect("Could not create map");
+
+            map.circular = true;
+            map
+        }
+
+        #[test]
+        fn wrap_rung_links_last_and_first_columns() {
+            let map = circular_map(vec!["-| | |-", "| | |"]);
+
+            assert_eq!(map.can_go_to_sides_for_coords(0, 0), Ok((true, false)));
+            assert_eq!(map.can_go_to_sides_for_coords(2, 0), Ok((false, true)));
+            assert_eq!(map.exit_for_entry("A"), Ok("3".to_owned()));
+            assert_eq!(map.exit_for_entry("B"), Ok("2".to_owned()));
+            assert_eq!(map.exit_for_entry("C"), Ok("1".to_owned()));
+            assert!(map.validate().is_ok());
+        }
+
+        #[test]
+        fn edge_rungs_are_ignored_when_not_circular() {
+            let mut map = circular_map(vec!["-| | |-"]);
+
+            map.circular = false;
+            assert_eq!(map.can_go_to_sides_for_coords(0, 0), Ok((false, false)));
+            assert_eq!(map.exit_for_entry("A"), Ok("1".to_owned()));
+            assert_eq!(map.exit_for_entry("C"), Ok("3".to_owned()));
+        }
+
+        #[test]
+        fn conflicts_across_the_wrap_are_not_validated() {
+            assert!(circular_map(vec!["-|--| |-"]).validate().is_err());
+            assert!(circular_map(vec!["-| |--|-"]).validate().is_err());
+        }
+
+        #[test]
+        fn half_wrap_rungs_are_not_validated() {
+            assert!(circular_map(vec!["-| | |"]).validate().is_err());
+            assert!(circular_map(vec!["| | |-"]).validate().is_err());
+        }
+
+        #[test]
+        fn wrap_rungs_are_rendered_on_row_edges() {
+            let map = circular_map(vec!["-| | |-", "|--| |"]);
+
+            assert_eq!(map.to_string(), "5 4\nA B C\n-| | |-\n|--| |\n1 2 3\n");
+        }
+    }
+
+    mod obstacles {
+        use super::*;
+        use crate::map::Outcome;
+
+        fn map_with_obstacles() -> Map {
+            "5 5\nA B C\n|--| |\n| | x\nx |--|\n1 2 3"
+                .parse::<Map>()
+                .unwrap()
+        }
+
+        #[test]
+        fn obstacles_are_columns() {
+            let map = map_with_obstacles();
+
+            assert_eq!(map.column_count(1), Ok(3));
+            assert_eq!(map.is_blocked(2, 1), Ok(true));
+            assert_eq!(map.is_blocked(1, 1), Ok(false));
+            assert!(map.validate().is_ok());
+            assert_eq!(
+                map.to_string(),
+                "5 5\nA B C\n|--| |\n| | x\nx |--|\n1 2 3\n"
+            );
+        }
+
+        #[test]
+        fn walkers_going_down_an_obstacle_are_trapped() {
+            let map = map_with_obstacles();
+
+            assert_eq!(
+                map.outcome_for_entry("A"),
+                Ok(Outcome::Exit("3".to_owned()))
+            );
+            assert_eq!(
+                map.outcome_for_entry("B"),
+                Ok(Outcome::Trapped { line: 2, column: 0 })
+            );
+            assert_eq!(
+                map.outcome_for_entry("C"),
+                Ok(Outcome::Trapped { line: 1, column: 2 })
+            );
+            assert_eq!(map.trace_for_entry("C"), Ok(vec![2, 2, 2]));
+            assert!(map.exit_for_entry("C").is_err());
+            assert!(map.solve().is_err());
+        }
+
+        #[test]
+        fn rungs_lead_walkers_away_from_obstacles() {
+            let map = "5 3\nA B C\nx--| |\n1 2 3".parse::<Map>().unwrap();
+
+            assert_eq!(map.exit_for_entry("A"), Ok("2".to_owned()));
+            assert_eq!(
+                map.outcome_for_entry("B"),
+                Ok(Outcome::Trapped { line: 0, column: 0 })
+            );
+        }
+    }
 }
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use crate::generator::{entry_label, exit_label};
+    use crate::map::Map;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    /// Removes the rungs that would conflict with the rung on their left (or the first one for the wrap rung).
+    fn without_conflicts(mut rungs: Vec<bool>, circular: bool) -> Vec<bool> {
+        let columns = rungs.len();
+
+        for slot in 0..columns {
+            let is_wrap = slot + 1 == columns;
+
+            if (slot > 0 && rungs[slot - 1]) || (is_wrap && (!circular || columns < 2 || rungs[0]))
+            {
+                rungs[slot] = false;
+            }
+        }
+        rungs
+    }
+
+    /// Generates valid ladders of up to 8 columns and 12 lines, shrinking towards smaller ones.
+    fn ladder() -> impl Strategy<Value = Map> {
+        (1usize..=8, any::<bool>()).prop_flat_map(|(columns, circular)| {
+            vec(vec(any::<bool>(), columns), 0..=12).prop_map(move |lines| {
+                let rungs = lines
+                    .into_iter()
+                    .map(|line| without_conflicts(line, circular))
+                    .collect::<Vec<_>>();
+
+                Map::from_rungs(
+                    (0..columns).map(entry_label).collect(),
+                    (0..columns).map(exit_label).collect(),
+                    &rungs,
+                    circular,
+                )
+            })
+        })
+    }
+
+    /// Returns the column reached by each entry.
+    fn exit_columns(map: &Map) -> Vec<usize> {
+        map.entries
+            .iter()
+            .map(|entry| {
+                let trace = map.trace_for_entry(entry).expect("Could not trace entry");
+
+                trace[trace.len() - 1]
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn any_text_gives_a_map_or_an_error(text in "[0-9 ]{0,6}\n(.{0,12}\n){0,8}", circular in any::<bool>()) {
+            if let Ok(mut map) = text.parse::<Map>() {
+                map.circular = circular;
+                let _ = map.validate();
+                for entry in map.entries.clone() {
+                    let _ = map.exit_for_entry(&entry);
+                }
+            }
+        }
+
+        #[test]
+        fn generated_ladders_are_valid(map in ladder()) {
+            prop_assert!(map.validate().is_ok());
+        }
+
+        #[test]
+        fn entries_and_exits_are_a_bijection(map in ladder()) {
+            let mut columns = exit_columns(&map);
+
+            columns.sort_unstable();
+            prop_assert_eq!(columns, (0..map.entries.len()).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn inverse_ladder_undoes_the_original(map in ladder()) {
+            let inverse = map.inverse();
+
+            for entry in &map.entries {
+                let exit = map.exit_for_entry(entry).expect("Could not find exit");
+
+                prop_assert_eq!(&inverse.exit_for_entry(&exit).expect("Could not find entry"), entry);
+            }
+        }
+
+        #[test]
+        fn ladder_stacked_with_its_mirror_is_the_identity(map in ladder()) {
+            let stacked = map.stack(&map.mirror());
+
+            prop_assert_eq!(stacked.height, map.height * 2);
+            prop_assert_eq!(exit_columns(&stacked), (0..map.entries.len()).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn rendering_then_parsing_keeps_the_result(map in ladder()) {
+            let mut parsed = map.to_string().parse::<Map>().expect("Could not parse rendered map");
+
+            // Circular mode is chosen when reading the map, it is not part of the text format
+            parsed.circular = map.circular;
+
+            prop_assert_eq!(&parsed.content, &map.content);
+            for entry in &map.entries {
+                prop_assert_eq!(parsed.exit_for_entry(entry), map.exit_for_entry(entry));
+            }
+        }
+    }
+}
@@ -1 +1,23 @@
+mod animation;
+mod batch;
+mod check;
+mod compact;
+mod cost;
+mod count;
+mod edit;
+mod error;
+mod fairness;
+mod fill;
+mod generator;
+mod layer;
+mod lint;
 mod map;
+mod map_properties;
+mod multiplayer;
+mod schema;
+mod sensitivity;
+mod stream;
+mod suggestion;
+mod svg;
+mod view;
+mod walker;
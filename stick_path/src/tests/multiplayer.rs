@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use crate::generator::{generate, Rng};
+    use crate::map::{Map, Outcome};
+    use crate::multiplayer::{play, Arrival, CollisionPolicy};
+
+    fn crossing() -> Map {
+        "5 4\nA B C\n|--| |\n| |--|\n1 2 3".parse::<Map>().unwrap()
+    }
+
+    fn arrivals(map: &Map, policy: CollisionPolicy) -> Vec<String> {
+        play(map, policy)
+            .unwrap()
+            .iter()
+            .map(Arrival::to_string)
+            .collect()
+    }
+
+    mod policies {
+        use super::*;
+
+        #[test]
+        fn waiting_walkers_let_the_first_entry_go() {
+            assert_eq!(
+                arrivals(&crossing(), CollisionPolicy::Wait),
+                ["A3 at tick 2", "B1 at tick 3", "C2 at tick 3"]
+            );
+        }
+
+        #[test]
+        fn bouncing_walkers_stay_on_their_pole() {
+            assert_eq!(
+                arrivals(&crossing(), CollisionPolicy::Bounce),
+                ["A1 at tick 2", "B2 at tick 2", "C3 at tick 2"]
+            );
+        }
+
+        #[test]
+        fn swapping_walkers_finish_as_when_solving() {
+            let mut rng = Rng::new(49);
+
+            for circular in [false, true] {
+                let map = generate(6, 20, 0.5, circular, &mut rng);
+
+                for arrival in play(&map, CollisionPolicy::Swap).unwrap() {
+                    assert_eq!(
+                        arrival.outcome,
+                        map.outcome_for_entry(&arrival.entry).unwrap()
+                    );
+                    assert_eq!(arrival.tick, map.height);
+                }
+            }
+        }
+
+        #[test]
+        fn only_walkers_sharing_a_rung_wait() {
+            let map = "5 3\nA B C\n|--| |\n1 2 3".parse::<Map>().unwrap();
+
+            assert_eq!(
+                arrivals(&map, CollisionPolicy::Wait),
+                ["A2 at tick 1", "B1 at tick 2", "C3 at tick 1"]
+            );
+        }
+
+        #[test]
+        fn policies_are_parsed() {
+            assert_eq!("wait".parse(), Ok(CollisionPolicy::Wait));
+            assert_eq!("bounce".parse(), Ok(CollisionPolicy::Bounce));
+            assert_eq!("swap".parse(), Ok(CollisionPolicy::Swap));
+            assert!("push".parse::<CollisionPolicy>().is_err());
+        }
+    }
+
+    mod arrivals {
+        use super::*;
+
+        #[test]
+        fn trapped_walkers_stop_at_the_obstacle() {
+            let map = "5 4\nA B C\n|--| |\nx | |\n1 2 3".parse::<Map>().unwrap();
+            let arrivals = play(&map, CollisionPolicy::Wait).unwrap();
+
+            assert_eq!(arrivals[1].outcome, Outcome::Trapped { line: 1, column: 0 });
+            assert_eq!(arrivals[1].tick, 3);
+            assert_eq!(
+                arrivals[1].to_string(),
+                "B trapped on line 2 column 1 at tick 3"
+            );
+        }
+
+        #[test]
+        fn empty_maps_are_finished_right_away() {
+            let map = "3 2\nA B\n1 2".parse::<Map>().unwrap();
+
+            assert_eq!(
+                arrivals(&map, CollisionPolicy::Wait),
+                ["A1 at tick 0", "B2 at tick 0"]
+            );
+        }
+
+        #[test]
+        fn races_looping_through_portals_are_errors() {
+            let map = "A B\n[a]\n| |\n| |\n[b -> a]\n| |\n| o\n1 2"
+                .parse::<Map>()
+                .unwrap();
+
+            assert!(play(&map, CollisionPolicy::Wait)
+                .unwrap_err()
+                .contains("loop forever"));
+        }
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use crate::map::{Map, Outcome};
+    use crate::schema::{MapSchema, Solution, SCHEMA_VERSION};
+
+    fn map() -> Map {
+        "9 5\nA B C\n|-3-| |\nx |-+2-|\n| | |\n1 2 3"
+            .parse::<Map>()
+            .unwrap()
+    }
+
+    #[test]
+    fn maps_round_trip_through_their_schema() {
+        let mut circular = "A B C\n-| | |-\n1 2 3".parse::<Map>().unwrap();
+
+        circular.circular = true;
+        for map in [map(), circular] {
+            let schema = MapSchema::from(map.clone());
+            let back = Map::try_from(schema.clone()).unwrap();
+
+            assert_eq!(schema.version, SCHEMA_VERSION);
+            assert_eq!(back.to_string(), map.to_string());
+            assert_eq!(back.circular, map.circular);
+        }
+    }
+
+    #[test]
+    fn invalid_schemas_are_rejected() {
+        let schema = MapSchema::from(map());
+
+        for invalid in [
+            MapSchema {
+                version: SCHEMA_VERSION + 1,
+                ..schema.clone()
+            },
+            MapSchema {
+                height: 1,
+                ..schema.clone()
+            },
+            MapSchema {
+                entries: vec!["A B".to_owned(), "C".to_owned()],
+                ..schema.clone()
+            },
+        ] {
+            assert!(Map::try_from(invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn solutions_keep_traces_and_outcomes() {
+        let solution = Solution::for_map(&map()).unwrap();
+        let outcomes = solution
+            .traces
+            .iter()
+            .map(|trace| trace.outcome.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Exit("3".to_owned()),
+                Outcome::Trapped { line: 1, column: 0 },
+                Outcome::Exit("2".to_owned())
+            ]
+        );
+        assert_eq!(
+            solution.traces[0].columns,
+            map().trace_for_entry("A").unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::*;
+
+        #[test]
+        fn maps_round_trip_through_json() {
+            let json = serde_json::to_string(&map()).unwrap();
+            let back: Map = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(
+                json,
+                r#"{"version":1,"width":9,"height":3,"circular":false,"entries":["A","B","C"],"exits":["1","2","3"],"lines":["|-3-| |","x |-+2-|","| | |"]}"#
+            );
+            assert_eq!(back.to_string(), map().to_string());
+        }
+
+        #[test]
+        fn maps_round_trip_through_message_pack() {
+            let bytes = rmp_serde::to_vec_named(&map()).unwrap();
+            let back: Map = rmp_serde::from_slice(&bytes).unwrap();
+
+            assert_eq!(back.to_string(), map().to_string());
+        }
+
+        #[test]
+        fn solutions_round_trip_through_json_and_message_pack() {
+            let solution = Solution::for_map(&map()).unwrap();
+            let json = serde_json::to_string(&solution).unwrap();
+            let bytes = rmp_serde::to_vec(&solution).unwrap();
+
+            assert!(json.contains(r#""outcome":{"trapped":{"line":1,"column":0}}"#));
+            assert!(json.contains(r#""outcome":{"exit":"3"}"#));
+            assert_eq!(serde_json::from_str::<Solution>(&json).unwrap(), solution);
+            assert_eq!(rmp_serde::from_slice::<Solution>(&bytes).unwrap(), solution);
+        }
+
+        #[test]
+        fn unknown_versions_are_rejected() {
+            let json = serde_json::to_string(&map())
+                .unwrap()
+                .replace("\"version\":1", "\"version\":2");
+            let err = serde_json::from_str::<Map>(&json).unwrap_err();
+
+            assert!(err.to_string().contains("Unsupported schema version 2"));
+            assert!(serde_json::from_str::<Solution>(r#"{"version":0,"traces":[]}"#).is_err());
+        }
+
+        #[test]
+        fn invalid_maps_are_rejected_on_deserialization() {
+            let json = r#"{"version":1,"width":5,"height":3,"circular":false,"entries":["A","B"],"exits":["1","2"],"lines":["| |"]}"#;
+
+            assert!(serde_json::from_str::<Map>(json).is_err());
+        }
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use crate::map::Map;
+    use crate::sensitivity::{sensitivity, CriticalRung};
+
+    fn map(exits: &str) -> Map {
+        format!("7 5\nA B C D\n|-| | |\n| |-| |\n|-| |-|\n{}", exits)
+            .parse::<Map>()
+            .unwrap()
+    }
+
+    mod critical_rungs {
+        use super::*;
+
+        #[test]
+        fn rungs_on_the_path_are_returned_with_the_entries_sharing_them() {
+            let rung = |line, column, shared_with: &str| CriticalRung {
+                line,
+                column,
+                shared_with: vec![shared_with.to_owned()],
+            };
+
+            assert_eq!(
+                map("1 2 3 4").critical_rungs("A"),
+                Ok(vec![rung(0, 0, "B"), rung(1, 1, "C"), rung(2, 2, "D")])
+            );
+            assert_eq!(
+                map("1 2 3 4").critical_rungs("C"),
+                Ok(vec![rung(1, 1, "A"), rung(2, 0, "B")])
+            );
+        }
+
+        #[test]
+        fn wrap_rungs_are_found_on_circular_maps() {
+            let mut map = "7 3\nA B C D\n-| | | |-\n1 2 3 4".parse::<Map>().unwrap();
+
+            map.circular = true;
+            assert_eq!(
+                map.critical_rungs("A"),
+                Ok(vec![CriticalRung {
+                    line: 0,
+                    column: 3,
+                    shared_with: vec!["D".to_owned()],
+                }])
+            );
+            assert_eq!(map.critical_rungs("B"), Ok(vec![]));
+        }
+
+        #[test]
+        fn unknown_entries_are_errors() {
+            assert!(map("1 2 3 4").critical_rungs("E").is_err());
+        }
+    }
+
+    mod sensitivity {
+        use super::*;
+
+        #[test]
+        fn removing_a_rung_changes_the_exits_of_the_entries_sharing_it() {
+            let report = sensitivity(&map("1 2 3 4")).unwrap();
+
+            assert_eq!(report.len(), 4);
+            assert_eq!(report[0].changed, vec!["A", "B"]);
+            assert_eq!(
+                report[3].to_string(),
+                "Rung on line 3 right of column 3: A D change exit"
+            );
+        }
+
+        #[test]
+        fn rungs_between_identical_exits_change_nothing() {
+            let report = sensitivity(&map("1 1 3 4")).unwrap();
+
+            assert_eq!(
+                report[2].to_string(),
+                "Rung on line 3 right of column 1: no exit changes"
+            );
+        }
+
+        #[test]
+        fn entries_trapped_by_obstacles_stay_trapped() {
+            let map = "7 4\nA B C D\n|-| | |\nx | | |\n1 2 3 4"
+                .parse::<Map>()
+                .unwrap();
+            let report = sensitivity(&map).unwrap();
+
+            // Without the rung, A is trapped and B walks down to its own exit
+            assert_eq!(report.len(), 1);
+            assert_eq!(report[0].changed, vec!["A", "B"]);
+        }
+
+        #[test]
+        fn exits_with_the_same_label_do_not_hide_obstacles() {
+            let map = "7 4\nA B C D\n|-| | |\nx | | |\n1 1 3 4"
+                .parse::<Map>()
+                .unwrap();
+            let report = sensitivity(&map).unwrap();
+
+            assert_eq!(
+                report[0].to_string(),
+                "Rung on line 1 right of column 1: A B change exit"
+            );
+        }
+    }
+}
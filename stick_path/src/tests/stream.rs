@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use crate::stream::{MapReader, ReadError};
+
+    fn read(text: &str) -> Vec<Result<String, ReadError>> {
+        MapReader::new(text.as_bytes())
+            .map(|map| map.map(|map| map.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn single_map_is_read() {
+        assert_eq!(
+            read("5 3\nA B C\n|--| |\n1 2 3\n"),
+            vec![Ok("5 3\nA B C\n|--| |\n1 2 3\n".to_owned())]
+        );
+    }
+
+    #[test]
+    fn maps_are_read_one_after_the_other() {
+        let maps = read("3 3\nA B\n|--|\n1 2\n\n\n3 3\nA B\n| |\n1 2\n3 3\nC D\n| |\n3 4");
+
+        assert_eq!(maps.len(), 3);
+        assert_eq!(maps[1], Ok("3 3\nA B\n| |\n1 2\n".to_owned()));
+        assert_eq!(maps[2], Ok("3 3\nC D\n| |\n3 4\n".to_owned()));
+    }
+
+    #[test]
+    fn blank_lines_inside_a_map_are_kept() {
+        let maps = read("3 4\nA B\n\n|--|\n1 2\n");
+
+        assert_eq!(maps, vec![Ok("3 4\nA B\n\n|--|\n1 2\n".to_owned())]);
+    }
+
+    #[test]
+    fn size_lines_narrower_than_the_labels_are_errors() {
+        let maps = read("1 3\nA B\n|--|\n1 2\n\n3 3\nA B\n| |\n1 2\n");
+
+        assert_eq!(maps.len(), 2);
+        assert!(matches!(maps[0], Err(ReadError::Parse(_))));
+        assert!(maps[1].is_ok());
+    }
+
+    #[test]
+    fn numeric_labels_are_not_size_lines() {
+        let maps = read("1 2\n|--|\n2 1\n\n3 3\n1 2\n| |\n1 2\n");
+
+        assert_eq!(
+            maps,
+            vec![
+                Ok("4 3\n1 2\n|--|\n2 1\n".to_owned()),
+                Ok("3 3\n1 2\n| |\n1 2\n".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_without_size_line_go_up_to_the_next_blank_line() {
+        let maps = read("A B\n|--|\n1 2\n\n3 3\nC D\n| |\n3 4\nE F\n| |\n5 6");
+
+        assert_eq!(maps.len(), 3);
+        assert_eq!(maps[0], Ok("4 3\nA B\n|--|\n1 2\n".to_owned()));
+        assert_eq!(maps[2], Ok("3 3\nE F\n| |\n5 6\n".to_owned()));
+    }
+
+    #[test]
+    fn truncated_maps_are_errors() {
+        let maps = read("3 3\nA B\n| |\n1 2\n3 5\nA B\n| |\n");
+
+        assert_eq!(maps.len(), 2);
+        assert!(maps[0].is_ok());
+        assert!(matches!(maps[1], Err(ReadError::Parse(_))));
+    }
+
+    #[test]
+    fn empty_input_has_no_map() {
+        assert!(read("").is_empty());
+        assert!(read("\n\n").is_empty());
+    }
+}
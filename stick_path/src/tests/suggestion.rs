@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use crate::map::Map;
+    use crate::suggestion::{suggest, RungChange};
+
+    fn simple_map() -> Map {
+        Map::from_size_and_content(
+            5,
+            5,
+            vec![
+                "A  B  C".to_owned(),
+                "|--|  |".to_owned(),
+                "|  |  |".to_owned(),
+                "|  |--|".to_owned(),
+                "1  2  3".to_owned(),
+            ],
+        )
+        .expect("Could not create map")
+    }
+
+    #[test]
+    fn current_exit_needs_no_change() {
+        let map = simple_map();
+        let suggestion = suggest(&map, "A", "3").expect("Could not suggest");
+
+        assert!(suggestion.changes.is_empty());
+        assert_eq!(suggestion.before, suggestion.after);
+    }
+
+    #[test]
+    fn single_change_is_found() {
+        let map = simple_map();
+        let suggestion = suggest(&map, "A", "2").expect("Could not suggest");
+
+        assert_eq!(suggestion.changes.len(), 1);
+        assert_eq!(suggestion.before, vec![0, 1, 1, 2]);
+        assert_eq!(suggestion.map.exit_for_entry("A"), Ok("2".to_owned()));
+        assert_eq!(
+            suggestion.map.trace_for_entry("A"),
+            Ok(suggestion.after.clone())
+        );
+    }
+
+    #[test]
+    fn obstacles_and_weights_are_kept() {
+        let map = "7 5\nA B C\n|--| |\n| | x\n| |-3-|\n1 2 3"
+            .parse::<Map>()
+            .unwrap();
+        let suggestion = suggest(&map, "C", "1").expect("Could not suggest");
+
+        // Going down the broken pole is not a way to the exit, even if it needs less changes
+        assert_eq!(suggestion.changes.len(), 3);
+        assert!(suggestion.changes.iter().any(|change| change.line == 1));
+        assert_eq!(suggestion.map.exit_for_entry("C"), Ok("1".to_owned()));
+        assert_eq!(
+            suggestion.map.trace_for_entry("C"),
+            Ok(suggestion.after.clone())
+        );
+        assert_eq!(suggestion.map.text_lines(), ["| |--|", "|--| x", "| |-3-|"]);
+    }
+
+    #[test]
+    fn conflicting_rungs_are_removed() {
+        let map = simple_map();
+        let suggestion = suggest(&map, "C", "1").expect("Could not suggest");
+
+        assert_eq!(suggestion.map.exit_for_entry("C"), Ok("1".to_owned()));
+        assert!(suggestion.map.validate().is_ok());
+        assert_eq!(suggestion.changes.len(), 3);
+        assert_eq!(
+            suggestion.changes[0],
+            RungChange {
+                line: 0,
+                column: 0,
+                added: false
+            }
+        );
+    }
+
+    #[test]
+    fn wrap_rungs_are_used_on_circular_maps() {
+        let mut map = simple_map();
+
+        map.circular = true;
+        let suggestion = suggest(&map, "A", "1").expect("Could not suggest");
+
+        assert_eq!(suggestion.changes.len(), 1);
+        assert_eq!(suggestion.map.exit_for_entry("A"), Ok("1".to_owned()));
+        assert!(suggestion.map.validate().is_ok());
+    }
+
+    #[test]
+    fn unknown_labels_are_errors() {
+        let map = simple_map();
+
+        assert!(suggest(&map, "D", "1").is_err());
+        assert!(suggest(&map, "A", "4").is_err());
+    }
+
+    #[test]
+    fn invalid_maps_are_errors() {
+        let wider_exits = "7 3\nA B C\n| | |\n1 2 3 4".parse::<Map>().unwrap();
+        let missing_poles = "5 3\nA B C\n| |\n1 2 3".parse::<Map>().unwrap();
+
+        assert!(suggest(&wider_exits, "A", "4").is_err());
+        assert!(suggest(&missing_poles, "A", "3").is_err());
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use crate::map::Map;
+
+    #[test]
+    fn rungs_poles_and_labels_are_drawn() {
+        let map = "5 4\nA B C\n|--| |\n| |--|\n1 2 3".parse::<Map>().unwrap();
+        let svg = map.to_svg().unwrap();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        // 3 poles and 2 rungs
+        assert_eq!(svg.matches("<line ").count(), 5);
+        assert!(svg.contains(">A</text>") && svg.contains(">3</text>"));
+    }
+
+    #[test]
+    fn wrap_rungs_weights_and_obstacles_are_drawn() {
+        let mut map = "9 4\nA B C\n-|-3-| |-\nx | |\n1 2 3"
+            .parse::<Map>()
+            .unwrap();
+
+        assert!(map.to_svg().unwrap().contains(">3</text>"));
+        map.circular = true;
+        map.content[0] = "-|-3-| |-".chars().collect();
+
+        let svg = map.to_svg().unwrap();
+
+        // 3 poles, 1 rung, and the 2 halves of the wrap rung
+        assert_eq!(svg.matches("<line ").count(), 6);
+        assert_eq!(svg.matches("<path ").count(), 1);
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        let map = "5 3\n<a> &\n| |\n1 2".parse::<Map>().unwrap();
+        let svg = map.to_svg().unwrap();
+
+        assert!(svg.contains(">&lt;a&gt;</text>") && svg.contains(">&amp;</text>"));
+    }
+}
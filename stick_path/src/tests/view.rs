@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::map::Map;
+    use crate::view::animation;
+
+    #[test]
+    fn maps_with_portals_are_validation_errors() {
+        let map = "A B\n[a -> a]\no |\n1 2".parse::<Map>().unwrap();
+
+        assert_eq!(
+            animation(&map, "A").unwrap_err().kind,
+            ErrorKind::Validation
+        );
+    }
+
+    #[test]
+    fn unknown_entries_are_solve_errors() {
+        let map = "5 3\nA B C\n|--| |\n1 2 3".parse::<Map>().unwrap();
+
+        assert_eq!(animation(&map, "D").unwrap_err().kind, ErrorKind::Solve);
+        assert!(animation(&map, "B").is_ok());
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use crate::generator::{generate, Rng};
+    use crate::map::{Direction, Map, Outcome};
+    use crate::walker::{simulate, Walker};
+
+    fn walker(label: &str, direction: Direction) -> Walker {
+        Walker {
+            label: label.to_owned(),
+            direction,
+        }
+    }
+
+    mod walk {
+        use super::*;
+
+        #[test]
+        fn walking_up_leads_back_to_the_entry() {
+            let mut rng = Rng::new(48);
+
+            for circular in [false, true] {
+                let map = generate(6, 20, 0.5, circular, &mut rng);
+
+                for entry in &map.entries {
+                    let exit = map.exit_for_entry(entry).unwrap();
+                    let (steps, outcome) = map.walk(&exit, Direction::Up).unwrap();
+
+                    assert_eq!(outcome, Outcome::Exit(entry.to_owned()));
+                    assert_eq!(steps.len(), map.height);
+                    assert_eq!(steps[0].0, map.height - 1);
+                }
+            }
+        }
+
+        #[test]
+        fn walkers_going_up_are_trapped_below_obstacles() {
+            let map = "9 5\nA B C\n|--| |\nx |-+2-|\n| | |\n1 2 3"
+                .parse::<Map>()
+                .unwrap();
+
+            assert_eq!(
+                map.walk("1", Direction::Up).unwrap().1,
+                Outcome::Trapped { line: 1, column: 0 }
+            );
+            assert_eq!(
+                map.walk("3", Direction::Up).unwrap().1,
+                Outcome::Exit("A".to_owned())
+            );
+        }
+
+        #[test]
+        fn labels_must_be_on_the_starting_side() {
+            let map = "5 3\nA B C\n|--| |\n1 2 3".parse::<Map>().unwrap();
+
+            assert!(map.walk("A", Direction::Up).is_err());
+            assert!(map.walk("1", Direction::Down).is_err());
+        }
+
+        #[test]
+        fn maps_with_portals_can_not_be_walked_up() {
+            let map = "A B\n[a -> b]\n|--o\n[b]\n| |\n1 2".parse::<Map>().unwrap();
+
+            assert!(map.walk("A", Direction::Down).is_ok());
+            assert!(map.walk("1", Direction::Up).is_err());
+        }
+    }
+
+    mod walkers {
+        use super::*;
+
+        #[test]
+        fn labels_choose_their_direction() {
+            let map = "5 3\nA B 1\n|--| |\n1 2 3".parse::<Map>().unwrap();
+
+            assert_eq!("2:up".parse(), Ok(walker("2", Direction::Up)));
+            assert_eq!(
+                Walker::for_label(&map, "B"),
+                Ok(walker("B", Direction::Down))
+            );
+            assert_eq!(Walker::for_label(&map, "3"), Ok(walker("3", Direction::Up)));
+            // Labels on both sides go down unless told otherwise
+            assert_eq!(
+                Walker::for_label(&map, "1"),
+                Ok(walker("1", Direction::Down))
+            );
+            assert_eq!(
+                Walker::for_label(&map, "1:up"),
+                Ok(walker("1", Direction::Up))
+            );
+            assert!(Walker::for_label(&map, "Z").is_err());
+            assert!(Walker::for_label(&map, "A:left").is_err());
+        }
+
+        #[test]
+        fn results_report_the_finishing_label() {
+            let map = "5 4\nA B C\n|--| |\n| |--|\n1 2 3".parse::<Map>().unwrap();
+            let results = simulate(&map, &Walker::both_ways(&map)).unwrap();
+            let lines = results.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+
+            assert_eq!(
+                lines,
+                vec!["A down 3", "B down 1", "C down 2", "1 up B", "2 up C", "3 up A"]
+            );
+        }
+    }
+}
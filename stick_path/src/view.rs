@@ -0,0 +1,105 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::{cursor, execute, terminal};
+
+use crate::animation::{Animation, Key};
+use crate::error::CliError;
+use crate::map::Map;
+
+/// Time in between two lines of the walk.
+const FRAME_DURATION: Duration = Duration::from_millis(500);
+
+const HELP: &str = "space: pause/resume - s: step - n/p: next/previous entry - q: quit";
+
+/// Converts a terminal key to an animation key.
+fn key_for_event(event: KeyEvent) -> Option<Key> {
+    if event.kind != KeyEventKind::Press {
+        return None;
+    }
+    match event.code {
+        KeyCode::Char(' ') => Some(Key::TogglePause),
+        KeyCode::Char('s') | KeyCode::Down => Some(Key::Step),
+        KeyCode::Char('n') | KeyCode::Right | KeyCode::Tab => Some(Key::NextEntry),
+        KeyCode::Char('p') | KeyCode::Left | KeyCode::BackTab => Some(Key::PreviousEntry),
+        KeyCode::Char('q') | KeyCode::Esc => Some(Key::Quit),
+        _ => None,
+    }
+}
+
+/// Draws the current frame of the animation, clearing the previous one.
+fn draw(out: &mut impl Write, animation: &Animation) -> io::Result<()> {
+    execute!(
+        out,
+        terminal::Clear(terminal::ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+    // Raw mode does not move back to the start of the line on '\n'
+    write!(
+        out,
+        "{}\r\n\r\n{}\r\n",
+        animation.frame().replace('\n', "\r\n"),
+        HELP
+    )?;
+    out.flush()
+}
+
+/// Runs the animation until the user quits.
+/// Terminal failures are `Io` errors, failures of the walk are `Solve` ones.
+fn run(out: &mut impl Write, animation: &mut Animation) -> Result<(), CliError> {
+    let mut last_frame = Instant::now();
+
+    loop {
+        draw(out, animation).map_err(terminal_error)?;
+
+        let timeout = FRAME_DURATION.saturating_sub(last_frame.elapsed());
+
+        if event::poll(timeout).map_err(terminal_error)? {
+            if let Event::Key(key_event) = event::read().map_err(terminal_error)? {
+                if let Some(key) = key_for_event(key_event) {
+                    if !animation.handle_key(key).map_err(CliError::solve)? {
+                        return Ok(());
+                    }
+                }
+            }
+        } else {
+            animation.tick().map_err(CliError::solve)?;
+            last_frame = Instant::now();
+        }
+    }
+}
+
+/// Reports a failure of the terminal.
+fn terminal_error(err: io::Error) -> CliError {
+    CliError::io(err.to_string())
+}
+
+/// Prepares the animation of the walk of the provided entry, before anything is drawn.
+/// Maps with portals are rejected as `Validation` errors, unknown entries as `Solve` ones.
+pub fn animation<'a>(map: &'a Map, entry: &str) -> Result<Animation<'a>, CliError> {
+    map.check_no_portals("Viewer")
+        .map_err(CliError::validation)?;
+
+    let (column, _) = map
+        .starting_coords_for_entry(entry)
+        .map_err(CliError::solve)?;
+
+    Animation::new(map, column).map_err(CliError::solve)
+}
+
+/// Animates the walk of the provided entry in the terminal.
+pub fn view(map: &Map, entry: &str) -> Result<(), CliError> {
+    let mut animation = animation(map, entry)?;
+    let mut out = io::stdout();
+
+    terminal::enable_raw_mode().map_err(terminal_error)?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide).map_err(terminal_error)?;
+
+    let result = run(&mut out, &mut animation);
+
+    // Always gives the terminal back, even if the animation failed
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen).map_err(terminal_error)?;
+    terminal::disable_raw_mode().map_err(terminal_error)?;
+    result
+}
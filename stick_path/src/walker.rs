@@ -0,0 +1,90 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::map::{Direction, Map, Outcome};
+
+/// Walker to simulate: the label it starts on and its direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Walker {
+    pub label: String,
+    pub direction: Direction,
+}
+
+impl FromStr for Walker {
+    type Err = String;
+
+    /// Parses `LABEL:up` or `LABEL:down`. Without direction, see Walker::for_label.
+    fn from_str(s: &str) -> Result<Walker, String> {
+        match s.rsplit_once(':') {
+            Some((label, direction)) if !label.is_empty() => Ok(Walker {
+                label: label.to_owned(),
+                direction: direction.parse()?,
+            }),
+            _ => Err(format!("Invalid walker {}", s)),
+        }
+    }
+}
+
+impl Walker {
+    /// Parses a walker of the map: a label alone goes down if it is an entry and up if it is an exit.
+    pub fn for_label(map: &Map, spec: &str) -> Result<Walker, String> {
+        if spec.contains(':') {
+            spec.parse()
+        } else if map.entries.iter().any(|entry| entry == spec) {
+            Ok(Walker {
+                label: spec.to_owned(),
+                direction: Direction::Down,
+            })
+        } else if map.exits.iter().any(|exit| exit == spec) {
+            Ok(Walker {
+                label: spec.to_owned(),
+                direction: Direction::Up,
+            })
+        } else {
+            Err(format!("Label {} not found in map", spec))
+        }
+    }
+
+    /// Returns every entry going down, then every exit going up.
+    pub fn both_ways(map: &Map) -> Vec<Walker> {
+        map.entries
+            .iter()
+            .map(|entry| (entry, Direction::Down))
+            .chain(map.exits.iter().map(|exit| (exit, Direction::Up)))
+            .map(|(label, direction)| Walker {
+                label: label.to_owned(),
+                direction,
+            })
+            .collect()
+    }
+}
+
+/// Where a walker finishes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkerResult {
+    pub walker: Walker,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for WalkerResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.walker.label, self.walker.direction, self.outcome
+        )
+    }
+}
+
+/// Walks every walker through the map, in the provided order.
+pub fn simulate(map: &Map, walkers: &[Walker]) -> Result<Vec<WalkerResult>, String> {
+    walkers
+        .iter()
+        .map(|walker| {
+            Ok(WalkerResult {
+                walker: walker.clone(),
+                outcome: map.walk(&walker.label, walker.direction)?.1,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,37 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the command line tool with the given arguments and standard input.
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_stick_path"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn maps_are_solved() {
+    let output = run(&[], "5 5\nA B C\n|--| |\n| |--|\n|--| |\n1 2 3\n");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "A3\nB2\nC1\n");
+}
+
+#[test]
+fn lines_missing_poles_are_validation_errors() {
+    let output = run(&[], "5 3\nA B C\n| |\n1 2 3\n");
+
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Line 1 has 2 poles for 3 entries"));
+}
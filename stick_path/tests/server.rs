@@ -0,0 +1,281 @@
+#![cfg(feature = "server")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use stick_path::server::{handle, Config, Request, Response, Server};
+
+const MAP: &str = "5 5\nA B C\n|--| |\n| |--|\n|--| |\n1 2 3\n";
+
+/// Starts a server on an ephemeral port, answering until the tests end.
+fn start(config: Config) -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0", config).unwrap();
+    let addr = server.local_addr().unwrap();
+
+    thread::spawn(move || server.run());
+    addr
+}
+
+/// Sends raw bytes and returns the status code and the body of the response.
+fn send(addr: SocketAddr, request: &[u8]) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let mut response = String::new();
+
+    stream.write_all(request).unwrap();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+
+    (status, body.to_owned())
+}
+
+fn post(addr: SocketAddr, path: &str, content_type: &str, body: &str) -> (u16, String) {
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+        path,
+        content_type,
+        body.len(),
+        body
+    );
+
+    send(addr, request.as_bytes())
+}
+
+fn get(addr: SocketAddr, path: &str) -> (u16, String) {
+    send(
+        addr,
+        format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes(),
+    )
+}
+
+#[test]
+fn text_maps_are_solved() {
+    let addr = start(Config::default());
+
+    assert_eq!(
+        post(addr, "/solve", "text/plain", MAP),
+        (
+            200,
+            r#"{"exits":[{"entry":"A","exit":"3"},{"entry":"B","exit":"2"},{"entry":"C","exit":"1"}]}"#
+                .to_owned()
+        )
+    );
+}
+
+#[test]
+fn json_maps_are_solved() {
+    let addr = start(Config::default());
+    let body = r#"{"map": "A B C\n-| | |-\n1 2 3", "circular": true}"#;
+
+    assert_eq!(
+        post(addr, "/solve", "application/json", body),
+        (
+            200,
+            r#"{"exits":[{"entry":"A","exit":"3"},{"entry":"B","exit":"2"},{"entry":"C","exit":"1"}]}"#
+                .to_owned()
+        )
+    );
+}
+
+#[test]
+fn trapped_entries_are_reported() {
+    let addr = start(Config::default());
+    let (status, body) = post(addr, "/solve", "text/plain", "A B\n|--|\nx |\n1 2");
+
+    assert_eq!(status, 200);
+    assert!(body.contains(r#"{"entry":"B","trapped":{"line":2,"column":1}}"#));
+}
+
+#[test]
+fn invalid_maps_are_rejected() {
+    let addr = start(Config::default());
+    let map = "5 3\nA B C\n|--|--|\n1 2 3\n";
+    let (status, body) = post(addr, "/solve", "text/plain", map);
+
+    assert_eq!(status, 422);
+    assert!(body.starts_with(r#"{"kind":"validation","#));
+    assert_eq!(post(addr, "/solve", "text/plain", "3 x\n").0, 400);
+    assert_eq!(post(addr, "/solve", "application/json", "{\"map\":").0, 400);
+}
+
+#[test]
+fn maps_are_validated() {
+    let addr = start(Config::default());
+    let (status, body) = post(addr, "/validate", "text/plain", "A B C\n|--|--|\n1 2 3");
+
+    assert_eq!(status, 200);
+    assert!(body.starts_with(r#"{"valid":false,"error":"#));
+    assert_eq!(
+        post(addr, "/validate", "text/plain", MAP),
+        (200, r#"{"valid":true}"#.to_owned())
+    );
+}
+
+#[test]
+fn maps_are_rendered_to_svg() {
+    let addr = start(Config::default());
+    let (status, body) = post(addr, "/render", "text/plain", MAP);
+
+    assert_eq!(status, 200);
+    assert!(body.starts_with("<svg"));
+    assert!(body.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn generated_maps_can_be_solved() {
+    let addr = start(Config::default());
+    let (status, map) = get(addr, "/generate?columns=6&height=12&density=0.4&seed=7");
+
+    assert_eq!(status, 200);
+    assert_eq!(
+        map,
+        get(addr, "/generate?columns=6&height=12&density=0.4&seed=7").1
+    );
+    assert_eq!(post(addr, "/solve", "text/plain", &map).0, 200);
+    assert_eq!(get(addr, "/generate?columns=6").0, 400);
+}
+
+#[test]
+fn generated_maps_are_limited() {
+    let addr = start(Config {
+        max_generated_cells: 100,
+        ..Config::default()
+    });
+
+    assert_eq!(get(addr, "/generate?columns=10&height=10").0, 200);
+    assert_eq!(get(addr, "/generate?columns=10&height=11").0, 400);
+}
+
+#[test]
+fn large_bodies_are_rejected() {
+    let addr = start(Config {
+        max_body: 16,
+        ..Config::default()
+    });
+
+    assert_eq!(post(addr, "/solve", "text/plain", MAP).0, 413);
+}
+
+#[test]
+fn slow_requests_time_out() {
+    let addr = start(Config {
+        timeout: Duration::from_millis(100),
+        ..Config::default()
+    });
+
+    assert_eq!(
+        send(addr, b"POST /solve HTTP/1.1\r\nContent-Length: 10\r\n").0,
+        408
+    );
+}
+
+#[test]
+fn slow_requests_are_cut_at_the_deadline() {
+    let addr = start(Config {
+        timeout: Duration::from_millis(200),
+        deadline: Duration::from_millis(500),
+        ..Config::default()
+    });
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let mut writer = stream.try_clone().unwrap();
+    let started = Instant::now();
+
+    // Each byte comes before the timeout, the request never ends
+    thread::spawn(move || {
+        for _ in 0..50 {
+            if writer.write_all(b"X").is_err() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    });
+
+    let mut response = String::new();
+
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 408"));
+    assert!(started.elapsed() < Duration::from_millis(2000));
+}
+
+#[test]
+fn workers_answer_one_connection_each() {
+    let addr = start(Config {
+        workers: 1,
+        timeout: Duration::from_millis(300),
+        ..Config::default()
+    });
+
+    // The only worker waits for the end of this request until it times out
+    let mut slow = TcpStream::connect(addr).unwrap();
+
+    slow.write_all(b"POST /solve HTTP/1.1\r\n").unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let started = Instant::now();
+
+    assert_eq!(post(addr, "/validate", "text/plain", MAP).0, 200);
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn panicking_handlers_are_server_errors() {
+    fn panicking(request: &Request, config: &Config) -> Response {
+        if request.path == "/panic" {
+            panic!("Handler failed");
+        }
+        handle(request, config)
+    }
+
+    let server = Server::bind(
+        "127.0.0.1:0",
+        Config {
+            workers: 1,
+            ..Config::default()
+        },
+    )
+    .unwrap()
+    .with_handler(panicking);
+    let addr = server.local_addr().unwrap();
+
+    thread::spawn(move || server.run());
+
+    // The only worker keeps answering after each panic
+    for _ in 0..2 {
+        let (status, body) = get(addr, "/panic");
+
+        assert_eq!(status, 500);
+        assert!(body.contains("Internal server error"));
+        assert_eq!(post(addr, "/validate", "text/plain", MAP).0, 200);
+    }
+}
+
+#[test]
+fn limits_must_leave_room_for_requests() {
+    for config in [
+        Config {
+            timeout: Duration::ZERO,
+            ..Config::default()
+        },
+        Config {
+            deadline: Duration::ZERO,
+            ..Config::default()
+        },
+        Config {
+            workers: 0,
+            ..Config::default()
+        },
+    ] {
+        assert!(Server::bind("127.0.0.1:0", config).is_err());
+    }
+}
+
+#[test]
+fn unknown_routes_are_rejected() {
+    let addr = start(Config::default());
+
+    assert_eq!(get(addr, "/unknown").0, 404);
+    assert_eq!(get(addr, "/solve").0, 405);
+}